@@ -1,4 +1,4 @@
 mod batch_backup;
 mod table_backup;
-pub use batch_backup::BatchBackup;
+pub use batch_backup::{BatchBackup, Query, TableOptions};
 pub use table_backup::TableBackup;
@@ -1,41 +1,177 @@
 use crate::backups::TableBackup;
-use crate::readers::MysqlReader;
-use crate::writers::ParquetWriterFactory;
+use crate::readers::{ColumnOptions, ColumnSelection, DataReader, InvalidValuePolicy, MysqlReader, SqliteReader};
+use crate::writers::{
+    DataWriterFactory, ObjectStoreWriterFactory, OutputFormat, PartitionExpression, PartitionedWriterFactory, parse_url,
+};
 use anyhow::{Result, bail};
-use std::{collections::HashSet, path::PathBuf};
+use mysql_async::Params;
+use object_store::ObjectStore;
+use object_store::path::Path;
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+///Settings of a single table in a BatchBackup. Settings left unset use the ones of the backup.
+#[derive(Debug, Clone, Default)]
+pub struct TableOptions {
+    ///Replaces the backup's output format (and the options of its writer) for this table.
+    pub output_format: Option<OutputFormat>,
+    ///When not empty, the table is written to a directory of Hive style partitions, such as <table>/currency_code=USD/part-0.parquet.
+    pub partitions: Vec<PartitionExpression>,
+    ///When set, the rows are read by this query instead of from the table, whose name then only names the output.
+    pub query: Option<Query>,
+    ///Columns of the table that are backed up. Defaults to all of them.
+    pub columns: ColumnSelection,
+    ///Predicate such as deleted_at IS NULL, used as the WHERE clause of the table so only matching rows are backed up.
+    pub filter: Option<String>,
+    ///Policies for invalid values in columns of this table, keyed by column name. These override the backup's column options.
+    pub column_policies: HashMap<String, InvalidValuePolicy>,
+}
+
+///A query backed up in place of a table, with the parameters bound to its placeholders.
+#[derive(Debug, Clone)]
+pub struct Query {
+    pub sql: String,
+    pub params: Params,
+}
 
 ///Simultaneously backs up multiple databases to their associated. parquet files.
 pub struct BatchBackup {
     root_directory: PathBuf,
-    tables: HashSet<String>,
+    tables: HashMap<String, TableOptions>,
+    column_options: ColumnOptions,
+    output_format: OutputFormat,
+    object_store: Option<(Arc<dyn ObjectStore>, Path)>,
 }
 
 impl BatchBackup {
     pub fn new(root_directory: PathBuf) -> BatchBackup {
         BatchBackup {
-            tables: HashSet::new(),
-            root_directory
+            tables: HashMap::new(),
+            root_directory,
+            column_options: ColumnOptions::default(),
+            output_format: OutputFormat::default(),
+            object_store: None,
+        }
+    }
+
+    ///Adds a table to the backup, returning its options so they can be overridden.
+    pub fn add_table(&mut self, name: String) -> &mut TableOptions {
+        self.tables.entry(name).or_default()
+    }
+
+    ///Adds a query to the backup, written to files named after name rather than any table. Returns its options so they
+    ///can be overridden. Fails if name is already used by a table or query of the backup, or is not a valid file name.
+    pub fn add_query(&mut self, name: String, sql: String, params: impl Into<Params>) -> Result<&mut TableOptions> {
+        if name.is_empty() || name.contains(['/', '\\']) {
+            bail!("Query name {name} cannot be used as a file name, it must be non-empty and not contain path separators.");
         }
+        if self.tables.contains_key(&name) {
+            bail!("Query name {name} is already used by another table or query of the backup.");
+        }
+        let table_options = self.add_table(name);
+        table_options.query = Some(Query { sql, params: params.into() });
+        Ok(table_options)
+    }
+
+    ///Sets the format of the files written for every table without its own. Defaults to Parquet.
+    pub fn set_output_format(&mut self, output_format: OutputFormat) {
+        self.output_format = output_format;
+    }
+
+    ///Sets how mysql columns are mapped to Arrow types for every table in the backup.
+    pub fn set_column_options(&mut self, column_options: ColumnOptions) {
+        self.column_options = column_options;
     }
 
-    pub fn add_table(&mut self, name: String) {
-        self.tables.insert(name);
+    ///Uploads the Parquet files of the backup below a URL such as s3://bucket/prefix instead of writing them to the root directory.
+    ///Reject files are still written to the root directory. See ObjectStoreWriterFactory::from_url for store_options.
+    pub fn set_object_store(&mut self, url: &str, store_options: &HashMap<String, String>) -> Result<()> {
+        self.object_store = Some(parse_url(url, store_options)?);
+        Ok(())
     }
 
     pub async fn execute(&self, pool: mysql_async::Pool) -> Result<()> {
+        self.execute_with_readers(|table_name, table_options, column_options, reject_path| {
+            let reader = match &table_options.query {
+                Some(query) => MysqlReader::from_query(pool.clone(), query.sql.clone(), query.params.clone(), 1000),
+                None => MysqlReader::new(pool.clone(), table_name.to_string(), 1000),
+            };
+            let mut reader = reader
+                .with_column_options(column_options)
+                .with_reject_path(reject_path)
+                .with_columns(table_options.columns.clone());
+            if let Some(filter) = &table_options.filter {
+                reader = reader.with_filter(filter.clone());
+            }
+            Box::new(reader)
+        })
+        .await
+    }
+
+    ///Backs up tables of a sqlite database file. Column options do not apply to sqlite tables and no rows are rejected.
+    ///Queries cannot have parameters, and tables cannot have column selections or filters.
+    pub async fn execute_sqlite(&self, path: PathBuf) -> Result<()> {
+        for (name, table_options) in self.tables.iter() {
+            if table_options.columns != ColumnSelection::All || table_options.filter.is_some() {
+                bail!("Table {name} cannot select columns or filter rows when backing up sqlite.");
+            }
+            if let Some(query) = &table_options.query
+                && query.params != Params::Empty
+            {
+                bail!("Query {name} cannot have parameters when backing up sqlite.");
+            }
+        }
+        self.execute_with_readers(|table_name, table_options, _, _| match &table_options.query {
+            Some(query) => Box::new(SqliteReader::from_query(path.clone(), query.sql.clone(), 1000)),
+            None => Box::new(SqliteReader::new(path.clone(), table_name.to_string(), 1000)),
+        })
+        .await
+    }
+
+    ///Backs up every table with the reader get_reader returns for it, given the table name and options, the column options
+    ///of the backup and the path rejected rows are written to.
+    pub async fn execute_with_readers<F>(&self, get_reader: F) -> Result<()>
+    where
+        F: Fn(&str, &TableOptions, ColumnOptions, PathBuf) -> Box<dyn DataReader>,
+    {
         let mut task_set = tokio::task::JoinSet::new();
 
-        for name in self.tables.iter() {
+        for (name, table_options) in self.tables.iter() {
             let table_name = name.clone();
-            let mut path = self.root_directory.clone();
-            let output_file_name = format!("{}.parquet", table_name);
-            path.push(output_file_name);
-            let pool = pool.clone();
+            let output_format = table_options.output_format.as_ref().unwrap_or(&self.output_format);
+            //Only local outputs are cleaned up by TableBackup, the object store writer aborts its own upload
+            let (writer, path): (Box<dyn DataWriterFactory>, Option<PathBuf>) = if let Some((store, prefix)) = &self.object_store {
+                let OutputFormat::Parquet(parquet_options) = output_format else {
+                    bail!("Table {table_name} can only be uploaded to an object store as Parquet.");
+                };
+                if !table_options.partitions.is_empty() {
+                    bail!("Table {table_name} cannot be partitioned when uploading to an object store.");
+                }
+                let object_path = prefix.child(format!("{}.parquet", table_name));
+                (Box::new(ObjectStoreWriterFactory::new(store.clone(), object_path).with_options(parquet_options.clone())), None)
+            } else if table_options.partitions.is_empty() {
+                let path = self.root_directory.join(format!("{}.{}", table_name, output_format.extension()));
+                (output_format.get_writer_factory(path.clone()), Some(path))
+            } else {
+                let path = self.root_directory.join(&table_name);
+                let partition_format = output_format.clone();
+                let writer = Box::new(PartitionedWriterFactory::new(
+                    path.clone(),
+                    output_format.extension(),
+                    table_options.partitions.clone(),
+                    move |path| partition_format.get_writer_factory(path),
+                ));
+                (writer, Some(path))
+            };
+            let mut reject_path = self.root_directory.clone();
+            reject_path.push(format!("{}.rejects.csv", table_name));
+            let mut column_options = self.column_options.clone();
+            column_options.column_policies.extend(table_options.column_policies.clone());
+            let reader = get_reader(&table_name, table_options, column_options, reject_path);
             task_set.spawn(async move {
-                let mut backup = TableBackup::new(path.clone());
-                let reader = Box::new(MysqlReader::new(pool, table_name.clone(), 1000));
-                let writer = Box::new(ParquetWriterFactory::new(path.clone()));
-                backup.execute(reader, writer).await
+                match path {
+                    Some(path) => TableBackup::new(path).execute(reader, writer).await,
+                    None => reader.read(writer).await,
+                }
             });
         }
 
@@ -57,3 +193,191 @@ impl BatchBackup {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::readers::MemoryReader;
+    use crate::writers::CsvOptions;
+    use crate::writers::test_util::batch;
+    use std::fs;
+
+    //Reads every table from memory, failing the ones named in failing after their first batch
+    fn get_reader(failing: &'static [&'static str]) -> impl Fn(&str, &TableOptions, ColumnOptions, PathBuf) -> Box<dyn DataReader> {
+        move |table_name, _, _, _| {
+            let reader = MemoryReader::new(batch(3).schema(), vec![batch(3), batch(3)]);
+            if failing.contains(&table_name) { Box::new(reader.with_failure_after(1)) } else { Box::new(reader) }
+        }
+    }
+
+    #[tokio::test]
+    async fn writes_every_table() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut backup = BatchBackup::new(directory.path().to_path_buf());
+        backup.add_table("users".to_string());
+        backup.add_table("accounts".to_string()).output_format = Some(OutputFormat::Csv(CsvOptions::default()));
+        backup.execute_with_readers(get_reader(&[])).await.unwrap();
+        assert!(directory.path().join("users.parquet").exists());
+        let contents = fs::read_to_string(directory.path().join("accounts.csv")).unwrap();
+        assert_eq!(contents, "id,name\n0,name 0\n1,name 1\n2,name 2\n0,name 0\n1,name 1\n2,name 2\n");
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_table_fails_backup() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut backup = BatchBackup::new(directory.path().to_path_buf());
+        backup.add_table("users".to_string());
+        backup.add_table("accounts".to_string());
+        assert!(backup.execute_with_readers(get_reader(&["accounts"])).await.is_err());
+        assert!(!directory.path().join("accounts.parquet").exists());
+        assert!(!directory.path().join("accounts.parquet.temp").exists());
+    }
+
+    #[tokio::test]
+    async fn failed_partitioned_table_leaves_nothing() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut backup = BatchBackup::new(directory.path().to_path_buf());
+        backup.add_table("accounts".to_string()).partitions = vec![PartitionExpression::bucket("id", 2)];
+        assert!(backup.execute_with_readers(get_reader(&["accounts"])).await.is_err());
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn backs_up_sqlite() {
+        let directory = tempfile::tempdir().unwrap();
+        let database = directory.path().join("state.sqlite");
+        rusqlite::Connection::open(&database)
+            .unwrap()
+            .execute_batch("CREATE TABLE devices (id INTEGER PRIMARY KEY, name TEXT); INSERT INTO devices VALUES (1, 'north');")
+            .unwrap();
+        let backup_directory = directory.path().join("backup");
+        fs::create_dir(&backup_directory).unwrap();
+        let mut backup = BatchBackup::new(backup_directory.clone());
+        backup.add_table("devices".to_string()).output_format = Some(OutputFormat::JsonLines);
+        backup.execute_sqlite(database.clone()).await.unwrap();
+        assert_eq!(fs::read_to_string(backup_directory.join("devices.jsonl")).unwrap(), "{\"id\":1,\"name\":\"north\"}\n");
+
+        backup.add_table("missing".to_string());
+        assert!(backup.execute_sqlite(database).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn backs_up_sqlite_queries() {
+        let directory = tempfile::tempdir().unwrap();
+        let database = directory.path().join("state.sqlite");
+        rusqlite::Connection::open(&database)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE devices (id INTEGER PRIMARY KEY, name TEXT, deleted_at TEXT); \
+                 INSERT INTO devices VALUES (1, 'north', NULL), (2, 'south', '2024-01-01');",
+            )
+            .unwrap();
+        let backup_directory = directory.path().join("backup");
+        fs::create_dir(&backup_directory).unwrap();
+        let mut backup = BatchBackup::new(backup_directory.clone());
+        let sql = "SELECT id, upper(name) AS name FROM devices WHERE deleted_at IS NULL".to_string();
+        backup.add_query("active_devices".to_string(), sql.clone(), ()).unwrap().output_format = Some(OutputFormat::JsonLines);
+        backup.execute_sqlite(database.clone()).await.unwrap();
+        assert_eq!(
+            fs::read_to_string(backup_directory.join("active_devices.jsonl")).unwrap(),
+            "{\"id\":1,\"name\":\"NORTH\"}\n"
+        );
+
+        backup.add_query("named_devices".to_string(), sql, vec!["north"]).unwrap();
+        assert!(backup.execute_sqlite(database).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn sqlite_rejects_column_selections() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut backup = BatchBackup::new(directory.path().to_path_buf());
+        backup.add_table("devices".to_string()).columns = ColumnSelection::Exclude(vec!["name".to_string()]);
+        assert!(backup.execute_sqlite(directory.path().join("state.sqlite")).await.is_err());
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn table_column_policies_override_backup() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut backup = BatchBackup::new(directory.path().to_path_buf());
+        backup.set_column_options(ColumnOptions {
+            column_policies: HashMap::from([
+                ("created_at".to_string(), InvalidValuePolicy::Null),
+                ("updated_at".to_string(), InvalidValuePolicy::Null),
+            ]),
+            ..Default::default()
+        });
+        backup.add_table("users".to_string()).column_policies =
+            HashMap::from([("created_at".to_string(), InvalidValuePolicy::Reject)]);
+        backup.add_table("accounts".to_string());
+        let options = std::sync::Mutex::new(HashMap::new());
+        backup
+            .execute_with_readers(|table_name, table_options, column_options, reject_path| {
+                options.lock().unwrap().insert(table_name.to_string(), column_options.clone());
+                get_reader(&[])(table_name, table_options, column_options, reject_path)
+            })
+            .await
+            .unwrap();
+        let options = options.into_inner().unwrap();
+        assert_eq!(options["users"].get_invalid_value_policy("created_at"), InvalidValuePolicy::Reject);
+        assert_eq!(options["users"].get_invalid_value_policy("updated_at"), InvalidValuePolicy::Null);
+        assert_eq!(options["accounts"].get_invalid_value_policy("created_at"), InvalidValuePolicy::Null);
+    }
+
+    #[tokio::test]
+    async fn passes_queries_to_readers() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut backup = BatchBackup::new(directory.path().to_path_buf());
+        backup.add_table("users".to_string());
+        backup.add_query("active_users".to_string(), "SELECT * FROM users WHERE id > ?".to_string(), (2,)).unwrap();
+        let queries = std::sync::Mutex::new(Vec::new());
+        backup
+            .execute_with_readers(|table_name, table_options, column_options, reject_path| {
+                queries.lock().unwrap().push((table_name.to_string(), table_options.query.clone()));
+                get_reader(&[])(table_name, table_options, column_options, reject_path)
+            })
+            .await
+            .unwrap();
+        let mut queries = queries.into_inner().unwrap();
+        queries.sort_by(|a, b| a.0.cmp(&b.0));
+        let query = queries[0].1.as_ref().unwrap();
+        assert_eq!(queries[0].0, "active_users");
+        assert_eq!(query.sql, "SELECT * FROM users WHERE id > ?");
+        assert_eq!(query.params, Params::Positional(vec![2.into()]));
+        assert!(queries[1].1.is_none());
+        assert!(directory.path().join("active_users.parquet").exists());
+    }
+
+    #[test]
+    fn rejects_invalid_query_names() {
+        let mut backup = BatchBackup::new(PathBuf::from("backups"));
+        backup.add_table("users".to_string());
+        backup.add_query("active_users".to_string(), "SELECT 1".to_string(), ()).unwrap();
+        for name in ["users", "active_users", "", "../users", "reports/active", "reports\\active"] {
+            assert!(backup.add_query(name.to_string(), "SELECT 2".to_string(), ()).is_err());
+        }
+        assert!(backup.tables["users"].query.is_none());
+        assert_eq!(backup.tables["active_users"].query.as_ref().unwrap().sql, "SELECT 1");
+    }
+
+    #[tokio::test]
+    async fn object_store_needs_parquet() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut backup = BatchBackup::new(directory.path().to_path_buf());
+        backup.set_object_store("memory:///", &HashMap::new()).unwrap();
+        backup.add_table("users".to_string()).output_format = Some(OutputFormat::JsonLines);
+        assert!(backup.execute_with_readers(get_reader(&[])).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn object_store_failure_keeps_local_files() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::write(directory.path().join("users.parquet"), "local file").unwrap();
+        let mut backup = BatchBackup::new(directory.path().to_path_buf());
+        backup.set_object_store("memory:///", &HashMap::new()).unwrap();
+        backup.add_table("users".to_string());
+        assert!(backup.execute_with_readers(get_reader(&["users"])).await.is_err());
+        assert_eq!(fs::read_to_string(directory.path().join("users.parquet")).unwrap(), "local file");
+    }
+}
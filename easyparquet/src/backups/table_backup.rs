@@ -2,7 +2,7 @@ use std::{fs, path::PathBuf};
 use std::io::ErrorKind;
 
 use crate::readers::DataReader;
-use crate::writers::DataWriterFactory;
+use crate::writers::{DataWriterFactory, append_extension};
 
 use anyhow::{Result, bail};
 
@@ -14,8 +14,7 @@ pub struct TableBackup {
 
 impl TableBackup {
     pub fn new(file_path: PathBuf) -> TableBackup {
-        let mut temp_path = file_path.clone();
-        temp_path.set_extension("temp");
+        let temp_path = append_extension(&file_path, "temp");
         TableBackup {
             temp_path,
             file_path,
@@ -23,13 +22,12 @@ impl TableBackup {
     }
 
     pub fn abort(&mut self) -> Result<()> {
-        if let Err(e) = fs::remove_file(&self.temp_path) {
-            if e.kind() != ErrorKind::NotFound {
-                bail!(e);
-            }
-        }
-        if let Err(e) = fs::remove_file(&self.file_path) {
-            if e.kind() != ErrorKind::NotFound {
+        for path in [&self.temp_path, &self.file_path] {
+            //Partitioned backups are written to directories rather than files
+            let result = if path.is_dir() { fs::remove_dir_all(path) } else { fs::remove_file(path) };
+            if let Err(e) = result
+                && e.kind() != ErrorKind::NotFound
+            {
                 bail!(e);
             }
         }
@@ -48,3 +46,70 @@ impl TableBackup {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::readers::MemoryReader;
+    use crate::writers::ParquetWriterFactory;
+    use crate::writers::test_util::batch;
+    use arrow::array::{RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::fs::File;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    async fn execute(file_path: &Path, reader: MemoryReader) -> Result<()> {
+        let writer = Box::new(ParquetWriterFactory::new(file_path.to_path_buf()));
+        TableBackup::new(file_path.to_path_buf()).execute(Box::new(reader), writer).await
+    }
+
+    #[tokio::test]
+    async fn writes_table() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("users.parquet");
+        execute(&path, MemoryReader::new(batch(3).schema(), vec![batch(3), batch(3)])).await.unwrap();
+        let rows: usize = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap()
+            .map(|batch| batch.unwrap().num_rows())
+            .sum();
+        assert_eq!(rows, 6);
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn reader_failure_removes_output() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("users.parquet");
+        fs::write(&path, "previous backup").unwrap();
+        let reader = MemoryReader::new(batch(3).schema(), vec![batch(3), batch(3)]).with_failure_after(1);
+        assert!(execute(&path, reader).await.is_err());
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn writer_failure_removes_output() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("users.parquet");
+        //Batches that do not match the schema the writer was set up with fail to be written
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Utf8, false)]));
+        let mismatched = RecordBatch::try_new(schema, vec![Arc::new(StringArray::from(vec!["1"]))]).unwrap();
+        let reader = MemoryReader::new(batch(3).schema(), vec![batch(3), mismatched]);
+        assert!(execute(&path, reader).await.is_err());
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn abort_removes_directories() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("accounts");
+        fs::create_dir_all(path.join("currency_code=USD")).unwrap();
+        fs::write(path.join("currency_code=USD").join("part-0.parquet"), "partition").unwrap();
+        fs::create_dir(directory.path().join("accounts.temp")).unwrap();
+        TableBackup::new(path).abort().unwrap();
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+}
@@ -1,10 +1,21 @@
-use dotenvy;
+use crate::readers::{ColumnSelection, InvalidValuePolicy};
+use crate::writers::OutputFormat;
+use std::collections::HashMap;
 
 #[derive(Debug)]
 pub struct Config {
     pub database_uri: String,
     pub backup_directory: String,
     pub database_tables: Vec<String>,
+    pub invalid_value_policy: InvalidValuePolicy,
+    pub json_inference_rows: usize,
+    pub output_format: OutputFormat,
+    pub table_output_formats: HashMap<String, OutputFormat>,
+    pub table_columns: HashMap<String, ColumnSelection>,
+    pub table_filters: HashMap<String, String>,
+    pub table_column_policies: HashMap<String, HashMap<String, InvalidValuePolicy>>,
+    pub object_store_url: Option<String>,
+    pub object_store_options: HashMap<String, String>,
 }
 
 fn get_env(key: &str) -> Result<String, String> {
@@ -14,6 +25,33 @@ fn get_env(key: &str) -> Result<String, String> {
     }
 }
 
+fn get_optional_env(key: &str) -> Option<String> {
+    dotenvy::var(key).ok()
+}
+
+//Reads a semicolon-separated list of table=value pairs
+fn get_table_env(key: &str) -> Result<Vec<(String, String)>, String> {
+    let mut entries = Vec::new();
+    for entry in get_optional_env(key).unwrap_or_default().split(";") {
+        if entry.trim().is_empty() {
+            continue;
+        }
+        let Some((table, value)) = entry.split_once("=") else {
+            return Err(format!("{key} entries take the form table=value, got {entry}."));
+        };
+        entries.push((table.trim().to_string(), value.trim().to_string()));
+    }
+    Ok(entries)
+}
+
+//Splits a comma-separated list, leaving out empty items
+fn split_list(list: &str) -> Vec<String> {
+    list.split(",")
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
 impl Config {
     pub fn build() -> Result<Config, String> {
         let database_uri = get_env("database_uri")?;
@@ -25,10 +63,84 @@ impl Config {
                 if !s_str.is_empty() { Some(s_str) } else { None }
             })
             .collect();
+        let invalid_value_policy = match get_optional_env("invalid_value_policy") {
+            Some(policy) => policy.parse()?,
+            None => InvalidValuePolicy::default(),
+        };
+        let json_inference_rows = match get_optional_env("json_inference_rows") {
+            Some(rows) => rows
+                .parse()
+                .map_err(|_| format!("json_inference_rows must be a number of rows, got {rows}."))?,
+            None => 0,
+        };
+        let mut output_format = match get_optional_env("output_format") {
+            Some(format) => format.parse()?,
+            None => OutputFormat::default(),
+        };
+        if let Some(compression) = get_optional_env("parquet_compression") {
+            let OutputFormat::Parquet(ref mut options) = output_format else {
+                return Err(String::from("parquet_compression only applies to the parquet output format."));
+            };
+            options.compression = Some(
+                compression
+                    .parse()
+                    .map_err(|e| format!("Invalid parquet_compression {compression}: {e}"))?,
+            );
+        }
+        if let Some(compression) = get_optional_env("ipc_compression") {
+            let OutputFormat::ArrowIpc(ref mut options) = output_format else {
+                return Err(String::from("ipc_compression only applies to the arrow and arrow_stream output formats."));
+            };
+            options.compression = Some(compression.parse()?);
+        }
+        let mut table_output_formats = HashMap::new();
+        for (table, format) in get_table_env("table_output_formats")? {
+            table_output_formats.insert(table, format.parse()?);
+        }
+        let mut table_columns = HashMap::new();
+        for (table, columns) in get_table_env("table_columns")? {
+            table_columns.insert(table, ColumnSelection::Include(split_list(&columns)));
+        }
+        for (table, columns) in get_table_env("table_excluded_columns")? {
+            if table_columns.contains_key(&table) {
+                return Err(format!("Table {table} cannot be in both table_columns and table_excluded_columns."));
+            }
+            table_columns.insert(table, ColumnSelection::Exclude(split_list(&columns)));
+        }
+        let table_filters = get_table_env("table_filters")?.into_iter().collect();
+        let mut table_column_policies = HashMap::new();
+        for (table, policies) in get_table_env("table_column_policies")? {
+            let mut column_policies = HashMap::new();
+            for entry in split_list(&policies) {
+                let Some((column, policy)) = entry.split_once(":") else {
+                    return Err(format!("table_column_policies columns take the form column:policy, got {entry}."));
+                };
+                column_policies.insert(column.trim().to_string(), policy.trim().parse()?);
+            }
+            table_column_policies.insert(table, column_policies);
+        }
+        let object_store_url = get_optional_env("object_store_url");
+        //object_store reads its configuration keys (such as aws_endpoint) from variables with the prefix of the backend
+        let object_store_options = dotenvy::vars()
+            .filter(|(key, _)| {
+                let key = key.to_lowercase();
+                key.starts_with("aws_") || key.starts_with("azure_") || key.starts_with("google_")
+            })
+            .map(|(key, value)| (key.to_lowercase(), value))
+            .collect();
         Ok(Config {
             database_uri,
             backup_directory,
             database_tables,
+            invalid_value_policy,
+            json_inference_rows,
+            output_format,
+            table_output_formats,
+            table_columns,
+            table_filters,
+            table_column_policies,
+            object_store_url,
+            object_store_options,
         })
     }
 
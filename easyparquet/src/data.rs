@@ -1 +1,3 @@
 pub mod columns;
+pub mod postgres_columns;
+pub mod sqlite_columns;
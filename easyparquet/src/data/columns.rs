@@ -1,19 +1,137 @@
 use anyhow::{bail, Context, Result};
-use arrow::array::Array;
-use arrow::datatypes::{DataType, Field};
-use chrono::NaiveDate;
+use arrow::array::timezone::Tz;
+use arrow::array::{
+    Array, ArrayRef, GenericBinaryBuilder, GenericStringBuilder, Int64Builder, ListBuilder, OffsetSizeTrait,
+    PrimitiveBuilder, StringArray, StringDictionaryBuilder,
+};
+use arrow::compute::kernels::cast_utils::parse_decimal;
+use arrow::datatypes::{
+    ArrowPrimitiveType, DataType, Decimal128Type, Decimal256Type, DecimalType, DurationMicrosecondType,
+    DurationMillisecondType, DurationSecondType, Field, Int8Type, Int16Type, Int32Type, Int64Type, Schema,
+    Time32MillisecondType, Time32SecondType, Time64MicrosecondType, TimeUnit, TimestampMicrosecondType,
+    TimestampMillisecondType, TimestampSecondType, UInt8Type, UInt16Type, UInt32Type, UInt64Type,
+};
+use arrow::error::ArrowError;
+use arrow::json::reader::{Decoder, ReaderBuilder, infer_json_schema_from_iterator};
+use chrono::{NaiveDate, NaiveDateTime, TimeZone};
 use mysql_async::Value;
+use mysql_async::consts::ColumnFlags;
 use mysql_async::consts::ColumnType as mysql_column_type;
-use rust_decimal::Decimal;
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
+//Collation id mysql reports for binary strings (BINARY, VARBINARY and BLOB), any other character set is treated as text
+const BINARY_CHARACTER_SET: u16 = 63;
+//Maximum length of a MEDIUMBLOB; columns at least this long use 64 bit offsets so a chunk cannot overflow Arrow's 2GB limit
+const LARGE_COLUMN_LENGTH: u32 = 16_777_215;
+pub(crate) const EXTENSION_NAME_KEY: &str = "ARROW:extension:name";
+pub(crate) const JSON_EXTENSION_NAME: &str = "arrow.json";
+//Name of the single field parsed JSON values are wrapped in, so that any JSON value (not just objects) can be decoded by arrow-json
+const JSON_VALUE_FIELD: &str = "value";
+//Prefix of the field metadata keys recording the source mysql column, so its definition can be rebuilt from the backup
+const METADATA_PREFIX: &str = "mysql.";
+//Field metadata key holding the allowed values of ENUM and SET columns as a JSON array
+const ALLOWED_VALUES_KEY: &str = "mysql.allowed_values";
+const WKB_EXTENSION_NAME: &str = "geoarrow.wkb";
+//Rows a column is sized for when the chunk size is not known, the default batch size of arrow-json
+const DEFAULT_CAPACITY: usize = 1024;
+///Schema metadata key GeoParquet readers look for the description of geometry columns under.
+pub const GEO_METADATA_KEY: &str = "geo";
+
 //Enums are used here instead of dyn/fat pointers for performance
 //Columns and schemas are automatically determined by an implementation of each Reader trait and passed to their writers as needed
 pub trait ColumnBuilder {
-    fn finish(self) -> Arc<dyn Array>;
+    fn finish(self) -> Result<Arc<dyn Array>>;
     fn push_null(&mut self) -> Result<()>;
     fn push_value(&mut self, value: Value) -> Result<()>;
+
+    ///Whether a non-null value can be represented by this column. Invalid values are handled by the column's InvalidValuePolicy instead of push_value.
+    fn is_valid(&self, _value: &Value) -> bool {
+        true
+    }
+
+    fn push_sentinel(&mut self) -> Result<()> {
+        bail!("Column type has no sentinel value.");
+    }
+}
+
+///What to do with values the Arrow type of a column cannot represent, such as mysql's 0000-00-00 zero dates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InvalidValuePolicy {
+    ///Fail the backup of the table.
+    #[default]
+    Fail,
+    ///Write NULL instead, which makes the Arrow field nullable.
+    Null,
+    ///Write the Unix epoch (or midnight for times of day) instead.
+    Sentinel,
+    ///Leave the whole row out of the backup and write it to the table's reject file.
+    Reject,
+}
+
+impl FromStr for InvalidValuePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fail" => Ok(InvalidValuePolicy::Fail),
+            "null" => Ok(InvalidValuePolicy::Null),
+            "sentinel" => Ok(InvalidValuePolicy::Sentinel),
+            "reject" => Ok(InvalidValuePolicy::Reject),
+            _ => Err(format!("Unknown invalid value policy {s}, expected one of fail, null, sentinel or reject.")),
+        }
+    }
+}
+
+///Options controlling how mysql columns are mapped to Arrow types.
+#[derive(Debug, Clone, Default)]
+pub struct ColumnOptions {
+    ///Emit TINYINT(1) columns, which mysql uses for BOOL, as Arrow Boolean instead of Int8/UInt8.
+    pub tinyint1_as_boolean: bool,
+    ///Emit TIME columns as an Arrow time of day (Time32, or Time64 for fractional seconds precision above 3) rather than Duration. These cannot hold negative values or values of 24 hours or more, which mysql allows, so such values are rejected.
+    pub time_as_time64: bool,
+    ///Time zone TIMESTAMP values are read in and annotated with, either an IANA name or an offset such as +02:00.
+    ///Readers set the connection's session time zone to this when given, otherwise they fill it in with the session time zone.
+    ///Sessions using the server's SYSTEM time zone are switched to UTC.
+    pub time_zone: Option<String>,
+    ///Policy for invalid temporal values in every column without an override in column_policies.
+    pub invalid_value_policy: InvalidValuePolicy,
+    ///Per-column overrides of invalid_value_policy, keyed by column name.
+    pub column_policies: HashMap<String, InvalidValuePolicy>,
+    ///When non-zero, JSON columns without an entry in json_types are parsed into nested Arrow types inferred from this many leading rows.
+    ///Rows with fields that were not seen while inferring fail the backup. When zero, JSON is kept as text.
+    pub json_inference_rows: usize,
+    ///Arrow types JSON columns are parsed into, keyed by column name. Readers add the inferred types to this.
+    pub json_types: HashMap<String, DataType>,
+    ///Allowed values of ENUM and SET columns, keyed by column name. Readers fill this in from information_schema.
+    pub enum_values: HashMap<String, Vec<String>>,
+    ///Spatial reference system ids of GEOMETRY columns restricted to a single SRID, keyed by column name. Readers fill this in from information_schema.
+    pub geometry_srids: HashMap<String, u32>,
+    ///Source definitions of columns, keyed by column name. Readers fill this in from information_schema and it is recorded in field metadata.
+    pub column_definitions: HashMap<String, ColumnDefinition>,
+}
+
+///Parts of a column's definition the client protocol does not report, as found in information_schema.COLUMNS.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnDefinition {
+    ///Full type of the column such as decimal(10,2) unsigned or enum('a','b').
+    pub column_type: String,
+    pub character_set: Option<String>,
+    pub collation: Option<String>,
+    ///Default value expression, None when the column has no default.
+    pub default: Option<String>,
+    ///PRI, UNI or MUL for indexed columns, empty otherwise.
+    pub key: String,
+    ///Extra attributes such as auto_increment or DEFAULT_GENERATED.
+    pub extra: String,
+    pub comment: String,
+}
+
+impl ColumnOptions {
+    pub fn get_invalid_value_policy(&self, column_name: &str) -> InvalidValuePolicy {
+        self.column_policies.get(column_name).copied().unwrap_or(self.invalid_value_policy)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -21,14 +139,143 @@ pub struct ColumnData {
     name: String,
     unsigned: bool,
     nullable: bool,
+    flags: ColumnFlags,
     column_type: mysql_column_type,
+    column_length: u32,
+    decimals: u8,
+    character_set: u16,
+    invalid_value_policy: InvalidValuePolicy,
+    allowed_values: Option<Vec<String>>,
+    srid: Option<u32>,
+    definition: Option<ColumnDefinition>,
     arrow_type: DataType,
 }
 
 impl ColumnData {
-    pub fn get_arrow_type(column_type: mysql_column_type, unsigned: bool) -> Result<DataType> {
+    ///Derives the precision of a DECIMAL column from its reported display length.
+    ///MySQL includes a sign character (signed columns only) and a decimal point (only when scale > 0) in the column length.
+    fn get_decimal_precision(column_length: u32, decimals: u8, unsigned: bool) -> Result<u8> {
+        let mut precision = column_length;
+        if decimals > 0 {
+            precision = precision.saturating_sub(1);
+        }
+        if !unsigned {
+            precision = precision.saturating_sub(1);
+        }
+        if precision == 0 || precision > Decimal256Type::MAX_PRECISION as u32 {
+            bail!("Invalid decimal precision {precision} derived from column length {column_length}.");
+        }
+        if decimals as u32 > precision {
+            bail!("Decimal scale {decimals} is larger than its precision {precision}.");
+        }
+        Ok(precision as u8)
+    }
+
+    fn get_string_type(column: &mysql_async::Column) -> DataType {
+        let large = match column.column_type() {
+            mysql_column_type::MYSQL_TYPE_MEDIUM_BLOB | mysql_column_type::MYSQL_TYPE_LONG_BLOB => true,
+            _ => column.column_length() >= LARGE_COLUMN_LENGTH,
+        };
+        match (column.character_set() == BINARY_CHARACTER_SET, large) {
+            (true, true) => DataType::LargeBinary,
+            (true, false) => DataType::Binary,
+            (false, true) => DataType::LargeUtf8,
+            (false, false) => DataType::Utf8,
+        }
+    }
+
+    ///Parses the allowed values out of an ENUM or SET column type as reported by information_schema, such as enum('a','b').
+    pub fn parse_allowed_values(column_type: &str) -> Result<Vec<String>> {
+        let start = column_type.find('(').context("Column type has no list of allowed values.")?;
+        let end = column_type.rfind(')').context("Column type has no list of allowed values.")?;
+        let mut chars = column_type[start + 1..end].chars().peekable();
+        let mut values = Vec::new();
+        while chars.peek().is_some() {
+            if chars.next() != Some('\'') {
+                bail!("Expected a quoted value in column type {column_type}.");
+            }
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    //Quotes inside a value are escaped by doubling them
+                    Some('\'') if chars.peek() == Some(&'\'') => {
+                        chars.next();
+                        value.push('\'');
+                    }
+                    Some('\'') => break,
+                    Some(c) => value.push(c),
+                    None => bail!("Unterminated value in column type {column_type}."),
+                }
+            }
+            values.push(value);
+            match chars.next() {
+                Some(',') | None => {}
+                Some(c) => bail!("Unexpected character {c} in column type {column_type}."),
+            }
+        }
+        Ok(values)
+    }
+
+    fn get_enum_type() -> DataType {
+        DataType::Dictionary(Box::new(DataType::Int16), Box::new(DataType::Utf8))
+    }
+
+    ///Infers the Arrow type of a JSON column from sample values, or None if there is nothing to infer from.
+    pub fn infer_json_type<'a>(values: impl Iterator<Item = &'a [u8]>) -> Result<Option<DataType>> {
+        let wrapped = values.map(|value| {
+            let value: serde_json::Value = serde_json::from_slice(value).map_err(|e| ArrowError::JsonError(e.to_string()))?;
+            Ok(serde_json::json!({ JSON_VALUE_FIELD: value }))
+        });
+        let schema = infer_json_schema_from_iterator(wrapped)?;
+        match schema.field_with_name(JSON_VALUE_FIELD) {
+            Ok(field) if *field.data_type() != DataType::Null => Ok(Some(field.data_type().clone())),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn get_arrow_type(column: &mysql_async::Column, options: &ColumnOptions) -> Result<DataType> {
+        let column_type = column.column_type();
+        let flags = column.flags();
+        let unsigned = flags.contains(ColumnFlags::UNSIGNED_FLAG);
         match column_type {
-            mysql_column_type::MYSQL_TYPE_VAR_STRING => Ok(DataType::Utf8),
+            //mysql sends ENUM and SET columns as strings, with a flag marking them
+            mysql_column_type::MYSQL_TYPE_ENUM => Ok(ColumnData::get_enum_type()),
+            mysql_column_type::MYSQL_TYPE_STRING if flags.contains(ColumnFlags::ENUM_FLAG) => Ok(ColumnData::get_enum_type()),
+            mysql_column_type::MYSQL_TYPE_SET => Ok(DataType::List(Arc::new(Field::new_list_field(ColumnData::get_enum_type(), true)))),
+            mysql_column_type::MYSQL_TYPE_STRING if flags.contains(ColumnFlags::SET_FLAG) => {
+                Ok(DataType::List(Arc::new(Field::new_list_field(ColumnData::get_enum_type(), true))))
+            },
+            mysql_column_type::MYSQL_TYPE_STRING
+            | mysql_column_type::MYSQL_TYPE_VAR_STRING
+            | mysql_column_type::MYSQL_TYPE_VARCHAR
+            | mysql_column_type::MYSQL_TYPE_BLOB
+            | mysql_column_type::MYSQL_TYPE_TINY_BLOB
+            | mysql_column_type::MYSQL_TYPE_MEDIUM_BLOB
+            | mysql_column_type::MYSQL_TYPE_LONG_BLOB => Ok(ColumnData::get_string_type(column)),
+            mysql_column_type::MYSQL_TYPE_TINY => {
+                if options.tinyint1_as_boolean && column.column_length() == 1 {
+                    Ok(DataType::Boolean)
+                } else if unsigned {
+                    Ok(DataType::UInt8)
+                } else {
+                    Ok(DataType::Int8)
+                }
+            },
+            //YEAR is sent as a two byte integer and is always flagged unsigned by mysql
+            mysql_column_type::MYSQL_TYPE_SHORT | mysql_column_type::MYSQL_TYPE_YEAR => {
+                if unsigned {
+                    Ok(DataType::UInt16)
+                } else {
+                    Ok(DataType::Int16)
+                }
+            },
+            mysql_column_type::MYSQL_TYPE_INT24 => {
+                if unsigned {
+                    Ok(DataType::UInt32)
+                } else {
+                    Ok(DataType::Int32)
+                }
+            },
             mysql_column_type::MYSQL_TYPE_LONG | mysql_column_type::MYSQL_TYPE_LONGLONG => {
                 if unsigned {
                     Ok(DataType::UInt64)
@@ -37,26 +284,163 @@ impl ColumnData {
                 }
             },
             mysql_column_type::MYSQL_TYPE_FLOAT => Ok(DataType::Float32),
-            mysql_column_type::MYSQL_TYPE_NEWDECIMAL => Ok(DataType::Decimal128(19, 2)),
+            mysql_column_type::MYSQL_TYPE_DOUBLE => Ok(DataType::Float64),
+            mysql_column_type::MYSQL_TYPE_NEWDECIMAL => {
+                let scale = column.decimals();
+                let precision = ColumnData::get_decimal_precision(column.column_length(), scale, unsigned)?;
+                if precision > Decimal128Type::MAX_PRECISION {
+                    Ok(DataType::Decimal256(precision, scale as i8))
+                } else {
+                    Ok(DataType::Decimal128(precision, scale as i8))
+                }
+            },
+            //BIT columns are at most 64 bits wide in mysql, FixedSizeBinary keeps anything wider intact
+            mysql_column_type::MYSQL_TYPE_BIT => {
+                let width = column.column_length();
+                if width <= 64 {
+                    Ok(DataType::UInt64)
+                } else {
+                    Ok(DataType::FixedSizeBinary(width.div_ceil(8) as i32))
+                }
+            },
+            mysql_column_type::MYSQL_TYPE_GEOMETRY => Ok(DataType::Binary),
+            mysql_column_type::MYSQL_TYPE_JSON => match options.json_types.get(column.name_str().as_ref()) {
+                Some(data_type) => Ok(data_type.clone()),
+                None => Ok(DataType::Utf8),
+            },
             mysql_column_type::MYSQL_TYPE_DATE => Ok(DataType::Date32),
-            mysql_column_type::MYSQL_TYPE_DATETIME => Ok(DataType::Timestamp(arrow::datatypes::TimeUnit::Microsecond, None)),
+            mysql_column_type::MYSQL_TYPE_DATETIME => Ok(DataType::Timestamp(get_time_unit(column.decimals()), None)),
+            mysql_column_type::MYSQL_TYPE_TIMESTAMP => {
+                let time_zone = options.time_zone.as_deref().context("No time zone available for TIMESTAMP column.")?;
+                time_zone.parse::<Tz>()?;
+                Ok(DataType::Timestamp(get_time_unit(column.decimals()), Some(time_zone.into())))
+            },
+            mysql_column_type::MYSQL_TYPE_TIME => {
+                let unit = get_time_unit(column.decimals());
+                if !options.time_as_time64 {
+                    Ok(DataType::Duration(unit))
+                } else if unit == TimeUnit::Microsecond {
+                    Ok(DataType::Time64(unit))
+                } else {
+                    //Time64 only holds micro and nanoseconds
+                    Ok(DataType::Time32(unit))
+                }
+            },
             _ => bail!("No matching Arrow schema type for column {:?}.", column_type),
         }
     }
 
-    pub fn new(name: String, unsigned: bool, nullable: bool, column_type: mysql_column_type) -> Result<ColumnData> {
-        let arrow_type = ColumnData::get_arrow_type(column_type, unsigned)?;
+    pub fn new(column: &mysql_async::Column, options: &ColumnOptions) -> Result<ColumnData> {
+        let name = column.name_str().into_owned();
+        let flags = column.flags();
+        //Note that the not_null flag means null is not allowed (mysql NOT NULL) so it needs to be inverted for Arrow nullable
+        let mut nullable = !flags.contains(ColumnFlags::NOT_NULL_FLAG);
+        let unsigned = flags.contains(ColumnFlags::UNSIGNED_FLAG);
+        let arrow_type = ColumnData::get_arrow_type(column, options)?;
+        let invalid_value_policy = options.get_invalid_value_policy(&name);
+        let temporal = matches!(
+            column.column_type(),
+            mysql_column_type::MYSQL_TYPE_DATE
+                | mysql_column_type::MYSQL_TYPE_DATETIME
+                | mysql_column_type::MYSQL_TYPE_TIMESTAMP
+                | mysql_column_type::MYSQL_TYPE_TIME
+        );
+        //Invalid values can only turn into nulls in temporal columns, other columns keep the nullability of the source
+        if temporal && invalid_value_policy == InvalidValuePolicy::Null {
+            nullable = true;
+        }
+        let allowed_values = options.enum_values.get(&name).cloned();
+        let srid = options.geometry_srids.get(&name).copied();
+        let definition = options.column_definitions.get(&name).cloned();
         Ok(ColumnData {
             name,
             unsigned,
             nullable,
-            column_type,
+            flags,
+            column_type: column.column_type(),
+            column_length: column.column_length(),
+            decimals: column.decimals(),
+            character_set: column.character_set(),
+            invalid_value_policy,
+            allowed_values,
+            srid,
+            definition,
             arrow_type,
         })
     }
 
+    ///Builds the GeoParquet file metadata describing the geometry columns, or None if there are none.
+    pub fn get_geo_metadata(columns: &[Arc<ColumnData>]) -> Option<String> {
+        let geometry_columns: serde_json::Map<String, serde_json::Value> = columns
+            .iter()
+            .filter(|data| data.column_type == mysql_column_type::MYSQL_TYPE_GEOMETRY)
+            .map(|data| {
+                //A null crs marks the coordinate reference system as unknown, which is the case for SRID 0 and unrestricted columns
+                let crs = match data.srid {
+                    Some(srid) if srid != 0 => serde_json::json!({ "id": { "authority": "EPSG", "code": srid } }),
+                    _ => serde_json::Value::Null,
+                };
+                let column = serde_json::json!({ "encoding": "WKB", "geometry_types": [], "crs": crs });
+                (data.name.clone(), column)
+            })
+            .collect();
+        let primary_column = geometry_columns.keys().next()?.clone();
+        let metadata = serde_json::json!({
+            "version": "1.1.0",
+            "primary_column": primary_column,
+            "columns": geometry_columns,
+        });
+        Some(metadata.to_string())
+    }
+
+    ///Describes the source mysql column under METADATA_PREFIX keys.
+    ///The nullable and key flags are those of the source column, the Arrow field may be nullable when the source is not.
+    fn get_source_metadata(&self) -> HashMap<String, String> {
+        let mut metadata = vec![
+            ("type", format!("{:?}", self.column_type)),
+            ("length", self.column_length.to_string()),
+            ("decimals", self.decimals.to_string()),
+            ("collation_id", self.character_set.to_string()),
+            ("unsigned", self.unsigned.to_string()),
+            ("nullable", (!self.flags.contains(ColumnFlags::NOT_NULL_FLAG)).to_string()),
+            ("primary_key", self.flags.contains(ColumnFlags::PRI_KEY_FLAG).to_string()),
+            ("unique_key", self.flags.contains(ColumnFlags::UNIQUE_KEY_FLAG).to_string()),
+            ("auto_increment", self.flags.contains(ColumnFlags::AUTO_INCREMENT_FLAG).to_string()),
+        ];
+        if let Some(definition) = &self.definition {
+            metadata.push(("column_type", definition.column_type.clone()));
+            if let Some(character_set) = &definition.character_set {
+                metadata.push(("character_set", character_set.clone()));
+            }
+            if let Some(collation) = &definition.collation {
+                metadata.push(("collation", collation.clone()));
+            }
+            if let Some(default) = &definition.default {
+                metadata.push(("default", default.clone()));
+            }
+            metadata.push(("key", definition.key.clone()));
+            metadata.push(("extra", definition.extra.clone()));
+            metadata.push(("comment", definition.comment.clone()));
+        }
+        metadata
+            .into_iter()
+            .map(|(key, value)| (format!("{METADATA_PREFIX}{key}"), value))
+            .collect()
+    }
+
     pub fn get_schema_field(&self) -> Field {
-        Field::new(self.name.clone(), self.arrow_type.clone(), self.nullable)
+        let mut metadata = HashMap::new();
+        if self.column_type == mysql_column_type::MYSQL_TYPE_JSON && self.arrow_type == DataType::Utf8 {
+            metadata.insert(EXTENSION_NAME_KEY.to_string(), JSON_EXTENSION_NAME.to_string());
+        }
+        if self.column_type == mysql_column_type::MYSQL_TYPE_GEOMETRY {
+            metadata.insert(EXTENSION_NAME_KEY.to_string(), WKB_EXTENSION_NAME.to_string());
+        }
+        if let Some(allowed_values) = &self.allowed_values {
+            metadata.insert(ALLOWED_VALUES_KEY.to_string(), serde_json::to_string(allowed_values).unwrap_or_default());
+        }
+        metadata.extend(self.get_source_metadata());
+        Field::new(self.name.clone(), self.arrow_type.clone(), self.nullable).with_metadata(metadata)
     }
 }
 
@@ -70,10 +454,17 @@ impl<T: ColumnBuilder> ColumnHolder<T> {
         ColumnHolder { data, builder }
     }
 
-    pub fn finish(self) -> Arc<dyn Array> {
+    pub fn finish(self) -> Result<Arc<dyn Array>> {
         self.builder.finish()
     }
 
+    ///Whether the value is invalid for this column and the row holding it should be written to the reject file instead.
+    pub fn rejects(&self, value: &mysql_async::Value) -> bool {
+        self.data.invalid_value_policy == InvalidValuePolicy::Reject
+            && *value != mysql_async::Value::NULL
+            && !self.builder.is_valid(value)
+    }
+
     pub fn push(&mut self, value: mysql_async::Value) -> Result<()> {
             if let mysql_async::Value::NULL = value {
                 if self.data.nullable {
@@ -83,26 +474,35 @@ impl<T: ColumnBuilder> ColumnHolder<T> {
                     bail!("Attempted to push null value to column that does not allow null values.");
                 }
             }
-        self.builder.push_value(value)?;
+        if !self.builder.is_valid(&value) {
+            match self.data.invalid_value_policy {
+                //push_value reports the error
+                InvalidValuePolicy::Fail => {},
+                InvalidValuePolicy::Null => return self.builder.push_null(),
+                InvalidValuePolicy::Sentinel => return self.builder.push_sentinel(),
+                InvalidValuePolicy::Reject => bail!("Invalid value {:?} in column {} should have been rejected.", value, self.data.name),
+            }
+        }
+        self.builder.push_value(value).with_context(|| format!("Could not write value to column {}.", self.data.name))?;
         Ok(())
     }
 }
 
-pub struct StringColumnBuilder {
-    builder: arrow::array::StringBuilder,
+pub struct StringColumnBuilder<O: OffsetSizeTrait> {
+    builder: GenericStringBuilder<O>,
 }
 
-impl StringColumnBuilder {
-    fn new() -> StringColumnBuilder {
+impl<O: OffsetSizeTrait> StringColumnBuilder<O> {
+    fn new() -> StringColumnBuilder<O> {
         StringColumnBuilder {
-            builder: arrow::array::StringBuilder::new(),
+            builder: GenericStringBuilder::<O>::new(),
         }
     }
 }
 
-impl ColumnBuilder for StringColumnBuilder {
-    fn finish(mut self) -> Arc<dyn Array> {
-        Arc::new(self.builder.finish())
+impl<O: OffsetSizeTrait> ColumnBuilder for StringColumnBuilder<O> {
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        Ok(Arc::new(self.builder.finish()))
     }
 
     fn push_null(&mut self) -> Result<()> {
@@ -112,7 +512,7 @@ impl ColumnBuilder for StringColumnBuilder {
 
     fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
         if let mysql_async::Value::Bytes(value) = value {
-            let result = String::from_utf8(value).unwrap();
+            let result = String::from_utf8(value).context("Value for text column is not valid UTF-8.")?;
             self.builder.append_value(result);
             Ok(())
         } else {
@@ -121,21 +521,21 @@ impl ColumnBuilder for StringColumnBuilder {
     }
 }
 
-pub struct Int64ColumnBuilder {
-    builder: arrow::array::Int64Builder,
+pub struct BinaryColumnBuilder<O: OffsetSizeTrait> {
+    builder: GenericBinaryBuilder<O>,
 }
 
-impl Int64ColumnBuilder {
-    fn new() -> Int64ColumnBuilder {
-        Int64ColumnBuilder {
-            builder: arrow::array::Int64Builder::new(),
+impl<O: OffsetSizeTrait> BinaryColumnBuilder<O> {
+    fn new() -> BinaryColumnBuilder<O> {
+        BinaryColumnBuilder {
+            builder: GenericBinaryBuilder::<O>::new(),
         }
     }
 }
 
-impl ColumnBuilder for Int64ColumnBuilder {
-    fn finish(mut self) -> Arc<dyn Array> {
-        Arc::new(self.builder.finish())
+impl<O: OffsetSizeTrait> ColumnBuilder for BinaryColumnBuilder<O> {
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        Ok(Arc::new(self.builder.finish()))
     }
 
     fn push_null(&mut self) -> Result<()> {
@@ -144,30 +544,54 @@ impl ColumnBuilder for Int64ColumnBuilder {
     }
 
     fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
-        if let mysql_async::Value::Int(value) = value {
+        if let mysql_async::Value::Bytes(value) = value {
             self.builder.append_value(value);
             Ok(())
         } else {
-            bail!("Value for column must be an integer, got {:?}.", value);
+            bail!("Value for column must be bytes, got {:?}.", value);
         }
     }
 }
 
-pub struct Uint64ColumnBuilder {
-    builder: arrow::array::UInt64Builder,
+fn new_enum_builder(allowed_values: &Option<Vec<String>>) -> Result<StringDictionaryBuilder<Int16Type>> {
+    match allowed_values {
+        //Seeding the dictionary with every allowed value keeps the keys in the order the values were declared in
+        Some(allowed_values) => {
+            let values = StringArray::from(allowed_values.clone());
+            Ok(StringDictionaryBuilder::new_with_dictionary(0, &values)?)
+        }
+        None => Ok(StringDictionaryBuilder::new()),
+    }
 }
 
-impl Uint64ColumnBuilder {
-    fn new() -> Uint64ColumnBuilder {
-        Uint64ColumnBuilder {
-            builder: arrow::array::UInt64Builder::new(),
-        }
+fn check_allowed_value(allowed_values: &Option<Vec<String>>, value: &str) -> Result<()> {
+    //mysql stores values it could not match as an empty string when not in strict mode
+    if let Some(allowed_values) = allowed_values
+        && !value.is_empty()
+        && !allowed_values.iter().any(|allowed| allowed == value)
+    {
+        bail!("Value {value} is not one of the allowed values {allowed_values:?}.");
+    }
+    Ok(())
+}
+
+pub struct EnumColumnBuilder {
+    builder: StringDictionaryBuilder<Int16Type>,
+    allowed_values: Option<Vec<String>>,
+}
+
+impl EnumColumnBuilder {
+    fn new(allowed_values: Option<Vec<String>>) -> Result<EnumColumnBuilder> {
+        Ok(EnumColumnBuilder {
+            builder: new_enum_builder(&allowed_values)?,
+            allowed_values,
+        })
     }
 }
 
-impl ColumnBuilder for Uint64ColumnBuilder {
-    fn finish(mut self) -> Arc<dyn Array> {
-        Arc::new(self.builder.finish())
+impl ColumnBuilder for EnumColumnBuilder {
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        Ok(Arc::new(self.builder.finish()))
     }
 
     fn push_null(&mut self) -> Result<()> {
@@ -176,31 +600,34 @@ impl ColumnBuilder for Uint64ColumnBuilder {
     }
 
     fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
-        //note: mysql_async uses Int64 to store unsigned long and longlong when receiving data from a database. Value needs to be converted to u64 here for accurate parquet representation.
-        if let mysql_async::Value::Int(value) = value {
-            self.builder.append_value(value as u64);
+        if let mysql_async::Value::Bytes(value) = value {
+            let value = String::from_utf8(value).context("Value for enum column is not valid UTF-8.")?;
+            check_allowed_value(&self.allowed_values, &value)?;
+            self.builder.append(value)?;
             Ok(())
         } else {
-            bail!("Value for column must be uint, got {:?}.", value);
+            bail!("Value for column must be an enum string, got {:?}.", value);
         }
     }
 }
 
-pub struct FloatColumnBuilder {
-    builder: arrow::array::Float32Builder,
+pub struct SetColumnBuilder {
+    builder: ListBuilder<StringDictionaryBuilder<Int16Type>>,
+    allowed_values: Option<Vec<String>>,
 }
 
-impl FloatColumnBuilder {
-    fn new() -> FloatColumnBuilder {
-        FloatColumnBuilder {
-            builder: arrow::array::Float32Builder::new(),
-        }
+impl SetColumnBuilder {
+    fn new(allowed_values: Option<Vec<String>>) -> Result<SetColumnBuilder> {
+        Ok(SetColumnBuilder {
+            builder: ListBuilder::new(new_enum_builder(&allowed_values)?),
+            allowed_values,
+        })
     }
 }
 
-impl ColumnBuilder for FloatColumnBuilder {
-    fn finish(mut self) -> Arc<dyn Array> {
-        Arc::new(self.builder.finish())
+impl ColumnBuilder for SetColumnBuilder {
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        Ok(Arc::new(self.builder.finish()))
     }
 
     fn push_null(&mut self) -> Result<()> {
@@ -209,33 +636,37 @@ impl ColumnBuilder for FloatColumnBuilder {
     }
 
     fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
-        if let mysql_async::Value::Float(value) = value {
-            self.builder.append_value(value);
+        if let mysql_async::Value::Bytes(value) = value {
+            let value = String::from_utf8(value).context("Value for set column is not valid UTF-8.")?;
+            //SET members cannot contain commas, so mysql sends them comma separated
+            for member in value.split(',').filter(|member| !member.is_empty()) {
+                check_allowed_value(&self.allowed_values, member)?;
+                self.builder.values().append(member)?;
+            }
+            self.builder.append(true);
             Ok(())
         } else {
-            bail!("Value for column must be float, got {:?}.", value);
+            bail!("Value for column must be a set string, got {:?}.", value);
         }
     }
 }
 
-//Todo: In future this should read the exact precision from the mysql table schema
-pub struct DecimalColumnBuilder {
-    builder: arrow::array::Decimal128Builder,
+//mysql sends BIT values as big endian bytes, only as many as the width of the column needs
+pub struct BitColumnBuilder {
+    builder: arrow::array::UInt64Builder,
 }
 
-impl DecimalColumnBuilder {
-
-    fn new() -> DecimalColumnBuilder {
-        DecimalColumnBuilder {
-            builder: arrow::array::Decimal128Builder::new().with_precision_and_scale(19, 2).unwrap(),
+impl BitColumnBuilder {
+    fn new() -> BitColumnBuilder {
+        BitColumnBuilder {
+            builder: arrow::array::UInt64Builder::new(),
         }
     }
 }
 
-impl ColumnBuilder for DecimalColumnBuilder {
-
-    fn finish(mut self) -> Arc<dyn Array> {
-        Arc::new(self.builder.finish())
+impl ColumnBuilder for BitColumnBuilder {
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        Ok(Arc::new(self.builder.finish()))
     }
 
     fn push_null(&mut self) -> Result<()> {
@@ -245,36 +676,35 @@ impl ColumnBuilder for DecimalColumnBuilder {
 
     fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
         if let mysql_async::Value::Bytes(value) = value {
-            //Need to convert from the mysql string representation of decimals to Parquet's mantissa and scale-based representation.
-            let s = str::from_utf8(&value).context("Could not convert decimal to in-memory string representation.")?;
-            let mut dec = Decimal::from_str(&s)?;
-            dec.rescale(2 as u32);
-            let value = dec.mantissa();
+            if value.len() > 8 {
+                bail!("Bit value of {} bytes does not fit in 64 bits.", value.len());
+            }
+            let value = value.iter().fold(0u64, |acc, byte| (acc << 8) | *byte as u64);
             self.builder.append_value(value);
             Ok(())
         } else {
-            bail!("Value for column must be Decimal, got {:?}.", value);
+            bail!("Value for column must be bits, got {:?}.", value);
         }
     }
 }
 
-pub struct DateColumnBuilder {
-    builder: arrow::array::Date32Builder,
+pub struct FixedBitColumnBuilder {
+    builder: arrow::array::FixedSizeBinaryBuilder,
+    width: usize,
 }
 
-impl DateColumnBuilder {
-
-    pub fn new() -> DateColumnBuilder {
-        DateColumnBuilder {
-            builder: arrow::array::Date32Builder::new()
+impl FixedBitColumnBuilder {
+    fn new(width: i32) -> FixedBitColumnBuilder {
+        FixedBitColumnBuilder {
+            builder: arrow::array::FixedSizeBinaryBuilder::new(width),
+            width: width as usize,
         }
     }
 }
 
-impl ColumnBuilder for DateColumnBuilder {
-
-    fn finish(mut self) -> Arc<dyn Array> {
-        Arc::new(self.builder.finish())
+impl ColumnBuilder for FixedBitColumnBuilder {
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        Ok(Arc::new(self.builder.finish()))
     }
 
     fn push_null(&mut self) -> Result<()> {
@@ -283,35 +713,37 @@ impl ColumnBuilder for DateColumnBuilder {
     }
 
     fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
-        if let mysql_async::Value::Date(year, month, day, _, _, _, _) = value {
-            let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).context("Epoch for Date comparison could not be converted")?;
-            let dt = NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32).context("Date for Date comparison could not be created.")?;
-            let value = dt.signed_duration_since(epoch).num_days() as i32;
-            self.builder.append_value(value);
+        if let mysql_async::Value::Bytes(value) = value {
+            if value.len() > self.width {
+                bail!("Bit value of {} bytes does not fit in {} bytes.", value.len(), self.width);
+            }
+            //Left pad so the value keeps its big endian meaning
+            let mut padded = vec![0u8; self.width - value.len()];
+            padded.extend_from_slice(&value);
+            self.builder.append_value(padded)?;
             Ok(())
         } else {
-            bail!("Value for column must be Date, got {:?}.", value);
+            bail!("Value for column must be bits, got {:?}.", value);
         }
     }
 }
 
-pub struct DateTimeColumnBuilder {
-    builder: arrow::array::TimestampMicrosecondBuilder,
+//mysql stores geometries as a 4 byte little endian SRID followed by standard WKB, only the WKB is kept
+pub struct GeometryColumnBuilder {
+    builder: arrow::array::BinaryBuilder,
 }
 
-impl DateTimeColumnBuilder {
-
-    pub fn new() -> DateTimeColumnBuilder {
-        DateTimeColumnBuilder {
-            builder: arrow::array::TimestampMicrosecondBuilder::new()
+impl GeometryColumnBuilder {
+    fn new() -> GeometryColumnBuilder {
+        GeometryColumnBuilder {
+            builder: arrow::array::BinaryBuilder::new(),
         }
     }
 }
 
-impl ColumnBuilder for DateTimeColumnBuilder {
-
-    fn finish(mut self) -> Arc<dyn Array> {
-        Arc::new(self.builder.finish())
+impl ColumnBuilder for GeometryColumnBuilder {
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        Ok(Arc::new(self.builder.finish()))
     }
 
     fn push_null(&mut self) -> Result<()> {
@@ -320,166 +752,1035 @@ impl ColumnBuilder for DateTimeColumnBuilder {
     }
 
     fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
-        if let mysql_async::Value::Date(year, month, day, hours, minutes, seconds, micro_seconds) = value {
-            let dt = NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32).context("Could not convert Date portion of DateTime.")?;
-                        let dt = dt.and_hms_micro_opt(hours as u32, minutes as u32, seconds as u32, micro_seconds).context("Could not convert Time portion of DateTime.")?;
-            let value = dt.and_utc().timestamp_micros();
-            self.builder.append_value(value);
-            Ok(())
+        if let mysql_async::Value::Bytes(value) = value {
+            match value.get(4) {
+                //The first WKB byte gives its byte order, 0 for big endian and 1 for little endian
+                Some(0) | Some(1) => {
+                    self.builder.append_value(&value[4..]);
+                    Ok(())
+                }
+                _ => bail!("Geometry value is not in mysql's SRID and WKB format."),
+            }
         } else {
-            bail!("Value for column must be DateTime, got {:?}.", value);
+            bail!("Value for column must be a geometry, got {:?}.", value);
         }
     }
 }
 
-pub enum Column {
-    String(ColumnHolder<StringColumnBuilder>),
-    Int64(ColumnHolder<Int64ColumnBuilder>),
-    Uint64(ColumnHolder<Uint64ColumnBuilder>),
-    Float(ColumnHolder<FloatColumnBuilder>),
-    Decimal(ColumnHolder<DecimalColumnBuilder>),
-    Date(ColumnHolder<DateColumnBuilder>),
-    DateTime(ColumnHolder<DateTimeColumnBuilder>),
+//Decodes JSON text into the nested Arrow type inferred for the column. Values are tokenized as they are pushed and built into
+//one array when the chunk is finished, which fails if any of them does not match the type
+pub struct ParsedJsonColumnBuilder {
+    decoder: Decoder,
+    data_type: DataType,
+    capacity: usize,
+    arrays: Vec<ArrayRef>,
 }
 
-impl Column {
-    pub fn finish(self) -> Arc<dyn Array> {
-        match self {
-            Column::String(data) => data.finish(),
-            Column::Int64(data) => data.finish(),
-            Column::Uint64(data) => data.finish(),
-            Column::Float(data) => data.finish(),
-            Column::Decimal(data) => data.finish(),
-            Column::Date(data) => data.finish(),
-            Column::DateTime(data) => data.finish(),
+impl ParsedJsonColumnBuilder {
+    fn new(data_type: DataType, capacity: usize) -> Result<ParsedJsonColumnBuilder> {
+        let schema = Schema::new(vec![Field::new(JSON_VALUE_FIELD, data_type.clone(), true)]);
+        let decoder = ReaderBuilder::new(Arc::new(schema))
+            .with_batch_size(capacity)
+            .with_strict_mode(true)
+            .build_decoder()?;
+        Ok(ParsedJsonColumnBuilder {
+            decoder,
+            data_type,
+            capacity,
+            arrays: Vec::new(),
+        })
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let batch = self.decoder.flush().context("JSON value does not match the inferred type of the column.")?;
+        if let Some(batch) = batch {
+            self.arrays.push(batch.column(0).clone());
         }
+        Ok(())
     }
 
-    pub fn from_data(data: Arc<ColumnData>) -> Result<Column> {
-        match data.column_type {
-            mysql_column_type::MYSQL_TYPE_LONG | mysql_column_type::MYSQL_TYPE_LONGLONG => {
-                if data.unsigned {
-                let column = Column::Uint64(ColumnHolder::new(data, Uint64ColumnBuilder::new()));
-                    Ok(column)
-                } else {
-                let column = Column::Int64(ColumnHolder::new(data, Int64ColumnBuilder::new()));
-                Ok(column)
-                }
-            }
-            mysql_column_type::MYSQL_TYPE_VAR_STRING => {
-                let column = Column::String(ColumnHolder::new(data, StringColumnBuilder::new()));
-                Ok(column)
-            }
-            mysql_column_type::MYSQL_TYPE_FLOAT => {
-                let column = Column::Float(ColumnHolder::new(data, FloatColumnBuilder::new()));
-                Ok(column)
-            },
-            mysql_column_type::MYSQL_TYPE_NEWDECIMAL => {
-                let column = Column::Decimal(ColumnHolder::new(data, DecimalColumnBuilder::new()));
-                Ok(column)
-            },
-            mysql_column_type::MYSQL_TYPE_DATE => {
-                let column = Column::Date(ColumnHolder::new(data, DateColumnBuilder::new()));
-                Ok(column)
-            },
-            mysql_column_type::MYSQL_TYPE_DATETIME => {
-                let column = Column::DateTime(ColumnHolder::new(data, DateTimeColumnBuilder::new()));
-                Ok(column)
-            }
-            _ => bail!("Unsupported column type".to_string()),
+    fn decode(&mut self, value: &[u8]) -> Result<()> {
+        //The decoder takes no more rows once it holds a full batch, which only happens for chunks larger than its capacity
+        if self.decoder.len() >= self.capacity {
+            self.flush()?;
+        }
+        let rows = self.decoder.len();
+        let mut wrapped = format!("{{\"{JSON_VALUE_FIELD}\":").into_bytes();
+        wrapped.extend_from_slice(value);
+        wrapped.push(b'}');
+        let decoded = self.decoder.decode(&wrapped)?;
+        if decoded != wrapped.len() || self.decoder.has_partial_record() || self.decoder.len() != rows + 1 {
+            bail!("JSON value is not a single document.");
         }
+        Ok(())
     }
+}
 
-    pub fn push(column: &mut Column, value: Value) -> Result<()> {
-        match column {
-            Column::String(holder) => {
-                holder.push(value)?;
-                Ok(())
-            }
-            Column::Int64(holder) => {
-                holder.push(value)?;
-                Ok(())
-            },
-            Column::Uint64(holder) => {
-                holder.push(value)?;
-                Ok(())
-            }
-            Column::Float(holder) => {
-                holder.push(value)?;
-                Ok(())
-            },
-            Column::Decimal(holder) => {
-                holder.push(value)?;
-                Ok(())
-            },
-            Column::Date(holder) => {
-                holder.push(value)?;
-                Ok(())
-            }
-            Column::DateTime(holder) => {
-                holder.push(value)?;
-                Ok(())
+impl ColumnBuilder for ParsedJsonColumnBuilder {
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        self.flush()?;
+        match self.arrays.len() {
+            0 => Ok(arrow::array::new_empty_array(&self.data_type)),
+            1 => Ok(self.arrays.remove(0)),
+            _ => {
+                let arrays: Vec<&dyn Array> = self.arrays.iter().map(|array| array.as_ref()).collect();
+                Ok(arrow::compute::concat(&arrays)?)
             }
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn push_null(&mut self) -> Result<()> {
+        self.decode(b"null")
+    }
 
-    mod column_data {
-        use super::*;
+    fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
+        if let mysql_async::Value::Bytes(value) = value {
+            self.decode(&value)
+        } else {
+            bail!("Value for column must be JSON, got {:?}.", value);
+        }
+    }
+}
 
-        use test_case::test_case;
+//Shared by all integer types; values are range checked rather than cast so that mismatched data is reported instead of wrapping.
+pub struct IntegerColumnBuilder<T: ArrowPrimitiveType> {
+    builder: PrimitiveBuilder<T>,
+}
 
-        macro_rules! type_tests {
-            ($ ($test_function:item)+ ) => {
-                $(
-        #[test_case(mysql_column_type::MYSQL_TYPE_VAR_STRING, false, DataType::Utf8; "String type")]
-        #[test_case(mysql_column_type::MYSQL_TYPE_LONG, true, DataType::UInt64; "Unsigned Long column type")]
-                #[test_case(mysql_column_type::MYSQL_TYPE_LONG, false, DataType::Int64; "Signed Long column type")]
-        #[test_case(mysql_column_type::MYSQL_TYPE_LONGLONG, true, DataType::UInt64; "Unsigned long long column type")]
-                #[test_case(mysql_column_type::MYSQL_TYPE_LONGLONG, false, DataType::Int64; "Signed long long column type")]
-        #[test_case(mysql_column_type::MYSQL_TYPE_FLOAT, false, DataType::Float32; "Float column type")]
-        #[test_case(mysql_column_type::MYSQL_TYPE_NEWDECIMAL, false, DataType::Decimal128(19, 2); "Signed decimal column type")]
-        #[test_case(mysql_column_type::MYSQL_TYPE_NEWDECIMAL, true, DataType::Decimal128(19, 2); "Unsigned decimal column type")]
-        #[test_case(mysql_column_type::MYSQL_TYPE_DATE, false, DataType::Date32; "Date column type")]
-        #[test_case(mysql_column_type::MYSQL_TYPE_DATETIME, false, DataType::Timestamp(arrow::datatypes::TimeUnit::Microsecond, None); "DateTime column type")]
-        
-        $test_function
-                )+
-            }
+impl<T: ArrowPrimitiveType> IntegerColumnBuilder<T> {
+    fn new() -> IntegerColumnBuilder<T> {
+        IntegerColumnBuilder {
+            builder: PrimitiveBuilder::<T>::new(),
         }
+    }
+}
 
-        type_tests! {
-            fn get_arrow_type_known(column_type: mysql_column_type, unsigned: bool, expected: DataType) {
-                assert_eq!(ColumnData::get_arrow_type(column_type, unsigned).unwrap(), expected);
-            }
-        }
+impl<T> ColumnBuilder for IntegerColumnBuilder<T>
+where
+    T: ArrowPrimitiveType,
+    T::Native: TryFrom<i64> + TryFrom<u64>,
+{
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        Ok(Arc::new(self.builder.finish()))
+    }
+
+    fn push_null(&mut self) -> Result<()> {
+        self.builder.append_null();
+        Ok(())
+    }
+
+    fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
+        //mysql_async only uses UInt for unsigned BIGINT values above i64::MAX, everything else arrives as Int
+        let converted = match value {
+            mysql_async::Value::Int(value) => T::Native::try_from(value).ok(),
+            mysql_async::Value::UInt(value) => T::Native::try_from(value).ok(),
+            _ => bail!("Value for column must be an integer, got {:?}.", value),
+        };
+        match converted {
+            Some(converted) => {
+                self.builder.append_value(converted);
+                Ok(())
+            }
+            None => bail!("Integer value {:?} is out of range for {:?}.", value, T::DATA_TYPE),
+        }
+    }
+}
+
+pub struct BooleanColumnBuilder {
+    builder: arrow::array::BooleanBuilder,
+}
+
+impl BooleanColumnBuilder {
+    fn new() -> BooleanColumnBuilder {
+        BooleanColumnBuilder {
+            builder: arrow::array::BooleanBuilder::new(),
+        }
+    }
+}
+
+impl ColumnBuilder for BooleanColumnBuilder {
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        Ok(Arc::new(self.builder.finish()))
+    }
+
+    fn push_null(&mut self) -> Result<()> {
+        self.builder.append_null();
+        Ok(())
+    }
+
+    fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
+        //mysql treats any non-zero TINYINT as true
+        if let mysql_async::Value::Int(value) = value {
+            self.builder.append_value(value != 0);
+            Ok(())
+        } else {
+            bail!("Value for column must be a boolean integer, got {:?}.", value);
+        }
+    }
+}
+
+pub struct FloatColumnBuilder {
+    builder: arrow::array::Float32Builder,
+}
+
+impl FloatColumnBuilder {
+    fn new() -> FloatColumnBuilder {
+        FloatColumnBuilder {
+            builder: arrow::array::Float32Builder::new(),
+        }
+    }
+}
+
+impl ColumnBuilder for FloatColumnBuilder {
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        Ok(Arc::new(self.builder.finish()))
+    }
+
+    fn push_null(&mut self) -> Result<()> {
+        self.builder.append_null();
+        Ok(())
+    }
+
+    fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
+        if let mysql_async::Value::Float(value) = value {
+            self.builder.append_value(value);
+            Ok(())
+        } else {
+            bail!("Value for column must be float, got {:?}.", value);
+        }
+    }
+}
+
+///Parses the mysql string representation of a decimal into the mantissa of an Arrow decimal with the given precision and scale.
+///Values that would need rounding or do not fit in the precision are rejected rather than silently truncated.
+fn parse_mysql_decimal<T: DecimalType>(value: &[u8], precision: u8, scale: i8) -> Result<T::Native> {
+    let s = str::from_utf8(value).context("Could not convert decimal to in-memory string representation.")?;
+    if let Some((_, fraction)) = s.split_once('.') {
+        let fraction_digits = fraction.trim_end_matches('0').len();
+        if fraction_digits > scale as usize {
+            bail!("Decimal value {s} has more than {scale} fractional digits and would be rounded.");
+        }
+    }
+    parse_decimal::<T>(s, precision, scale).with_context(|| format!("Decimal value {s} does not fit in Decimal({precision}, {scale})."))
+}
+
+pub struct DoubleColumnBuilder {
+    builder: arrow::array::Float64Builder,
+}
+
+impl DoubleColumnBuilder {
+    fn new() -> DoubleColumnBuilder {
+        DoubleColumnBuilder {
+            builder: arrow::array::Float64Builder::new(),
+        }
+    }
+}
+
+impl ColumnBuilder for DoubleColumnBuilder {
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        Ok(Arc::new(self.builder.finish()))
+    }
+
+    fn push_null(&mut self) -> Result<()> {
+        self.builder.append_null();
+        Ok(())
+    }
+
+    fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
+        if let mysql_async::Value::Double(value) = value {
+            self.builder.append_value(value);
+            Ok(())
+        } else {
+            bail!("Value for column must be double, got {:?}.", value);
+        }
+    }
+}
+
+pub struct DecimalColumnBuilder {
+    builder: arrow::array::Decimal128Builder,
+    precision: u8,
+    scale: i8,
+}
+
+impl DecimalColumnBuilder {
+
+    fn new(precision: u8, scale: i8) -> Result<DecimalColumnBuilder> {
+        Ok(DecimalColumnBuilder {
+            builder: arrow::array::Decimal128Builder::new().with_precision_and_scale(precision, scale)?,
+            precision,
+            scale,
+        })
+    }
+}
+
+impl ColumnBuilder for DecimalColumnBuilder {
+
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        Ok(Arc::new(self.builder.finish()))
+    }
+
+    fn push_null(&mut self) -> Result<()> {
+        self.builder.append_null();
+        Ok(())
+    }
+
+    fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
+        if let mysql_async::Value::Bytes(value) = value {
+            //Need to convert from the mysql string representation of decimals to Parquet's mantissa and scale-based representation.
+            let value = parse_mysql_decimal::<Decimal128Type>(&value, self.precision, self.scale)?;
+            self.builder.append_value(value);
+            Ok(())
+        } else {
+            bail!("Value for column must be Decimal, got {:?}.", value);
+        }
+    }
+}
+
+//Used for decimals with a precision above 38, which MySQL allows up to 65
+pub struct Decimal256ColumnBuilder {
+    builder: arrow::array::Decimal256Builder,
+    precision: u8,
+    scale: i8,
+}
+
+impl Decimal256ColumnBuilder {
+
+    fn new(precision: u8, scale: i8) -> Result<Decimal256ColumnBuilder> {
+        Ok(Decimal256ColumnBuilder {
+            builder: arrow::array::Decimal256Builder::new().with_precision_and_scale(precision, scale)?,
+            precision,
+            scale,
+        })
+    }
+}
+
+impl ColumnBuilder for Decimal256ColumnBuilder {
+
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        Ok(Arc::new(self.builder.finish()))
+    }
+
+    fn push_null(&mut self) -> Result<()> {
+        self.builder.append_null();
+        Ok(())
+    }
+
+    fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
+        if let mysql_async::Value::Bytes(value) = value {
+            let value = parse_mysql_decimal::<Decimal256Type>(&value, self.precision, self.scale)?;
+            self.builder.append_value(value);
+            Ok(())
+        } else {
+            bail!("Value for column must be Decimal, got {:?}.", value);
+        }
+    }
+}
+
+pub struct DateColumnBuilder {
+    builder: arrow::array::Date32Builder,
+}
+
+impl DateColumnBuilder {
+
+    pub fn new() -> DateColumnBuilder {
+        DateColumnBuilder {
+            builder: arrow::array::Date32Builder::new()
+        }
+    }
+}
+
+impl ColumnBuilder for DateColumnBuilder {
+
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        Ok(Arc::new(self.builder.finish()))
+    }
+
+    fn push_null(&mut self) -> Result<()> {
+        self.builder.append_null();
+        Ok(())
+    }
+
+    fn is_valid(&self, value: &mysql_async::Value) -> bool {
+        match value {
+            mysql_async::Value::Date(year, month, day, _, _, _, _) => NaiveDate::from_ymd_opt(*year as i32, *month as u32, *day as u32).is_some(),
+            _ => true,
+        }
+    }
+
+    fn push_sentinel(&mut self) -> Result<()> {
+        self.builder.append_value(0);
+        Ok(())
+    }
+
+    fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
+        if let mysql_async::Value::Date(year, month, day, _, _, _, _) = value {
+            let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).context("Epoch for Date comparison could not be converted")?;
+            let dt = NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32).context("Date for Date comparison could not be created.")?;
+            let value = dt.signed_duration_since(epoch).num_days() as i32;
+            self.builder.append_value(value);
+            Ok(())
+        } else {
+            bail!("Value for column must be Date, got {:?}.", value);
+        }
+    }
+}
+
+fn to_naive_datetime(year: u16, month: u8, day: u8, hours: u8, minutes: u8, seconds: u8, micro_seconds: u32) -> Result<NaiveDateTime> {
+    let dt = NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32).context("Could not convert Date portion of DateTime.")?;
+    dt.and_hms_micro_opt(hours as u32, minutes as u32, seconds as u32, micro_seconds).context("Could not convert Time portion of DateTime.")
+}
+
+///Gets the unit of a temporal column, the coarsest one that holds its fractional seconds precision.
+fn get_time_unit(decimals: u8) -> TimeUnit {
+    match decimals {
+        0 => TimeUnit::Second,
+        1..=3 => TimeUnit::Millisecond,
+        _ => TimeUnit::Microsecond,
+    }
+}
+
+fn from_micros(micros: i64, unit: TimeUnit) -> i64 {
+    match unit {
+        TimeUnit::Second => micros.div_euclid(1_000_000),
+        TimeUnit::Millisecond => micros.div_euclid(1_000),
+        _ => micros,
+    }
+}
+
+//Temporal values are collected as integers in the column's unit and given their Arrow type when the chunk is finished
+fn finish_timestamp(mut builder: Int64Builder, unit: TimeUnit, time_zone: Option<Arc<str>>) -> Arc<dyn Array> {
+    let values = builder.finish();
+    match unit {
+        TimeUnit::Second => Arc::new(values.reinterpret_cast::<TimestampSecondType>().with_timezone_opt(time_zone)),
+        TimeUnit::Millisecond => Arc::new(values.reinterpret_cast::<TimestampMillisecondType>().with_timezone_opt(time_zone)),
+        _ => Arc::new(values.reinterpret_cast::<TimestampMicrosecondType>().with_timezone_opt(time_zone)),
+    }
+}
+
+pub struct DateTimeColumnBuilder {
+    builder: Int64Builder,
+    unit: TimeUnit,
+}
+
+impl DateTimeColumnBuilder {
+
+    pub fn new(unit: TimeUnit) -> DateTimeColumnBuilder {
+        DateTimeColumnBuilder {
+            builder: Int64Builder::new(),
+            unit,
+        }
+    }
+}
+
+impl ColumnBuilder for DateTimeColumnBuilder {
+
+    fn finish(self) -> Result<Arc<dyn Array>> {
+        Ok(finish_timestamp(self.builder, self.unit, None))
+    }
+
+    fn push_null(&mut self) -> Result<()> {
+        self.builder.append_null();
+        Ok(())
+    }
+
+    fn is_valid(&self, value: &mysql_async::Value) -> bool {
+        match value {
+            mysql_async::Value::Date(year, month, day, hours, minutes, seconds, micro_seconds) => {
+                to_naive_datetime(*year, *month, *day, *hours, *minutes, *seconds, *micro_seconds).is_ok()
+            },
+            _ => true,
+        }
+    }
+
+    fn push_sentinel(&mut self) -> Result<()> {
+        self.builder.append_value(0);
+        Ok(())
+    }
+
+    fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
+        if let mysql_async::Value::Date(year, month, day, hours, minutes, seconds, micro_seconds) = value {
+            let dt = to_naive_datetime(year, month, day, hours, minutes, seconds, micro_seconds)?;
+            let value = from_micros(dt.and_utc().timestamp_micros(), self.unit);
+            self.builder.append_value(value);
+            Ok(())
+        } else {
+            bail!("Value for column must be DateTime, got {:?}.", value);
+        }
+    }
+}
+
+//mysql sends TIMESTAMP values as wall clock time in the session time zone, so they are converted back to UTC for Arrow
+pub struct TimestampColumnBuilder {
+    builder: Int64Builder,
+    unit: TimeUnit,
+    time_zone_name: Arc<str>,
+    time_zone: Tz,
+}
+
+impl TimestampColumnBuilder {
+
+    pub fn new(unit: TimeUnit, time_zone: &Arc<str>) -> Result<TimestampColumnBuilder> {
+        Ok(TimestampColumnBuilder {
+            builder: Int64Builder::new(),
+            unit,
+            time_zone_name: time_zone.clone(),
+            time_zone: time_zone.parse()?,
+        })
+    }
+}
+
+impl ColumnBuilder for TimestampColumnBuilder {
+
+    fn finish(self) -> Result<Arc<dyn Array>> {
+        Ok(finish_timestamp(self.builder, self.unit, Some(self.time_zone_name)))
+    }
+
+    fn push_null(&mut self) -> Result<()> {
+        self.builder.append_null();
+        Ok(())
+    }
+
+    fn is_valid(&self, value: &mysql_async::Value) -> bool {
+        match value {
+            mysql_async::Value::Date(year, month, day, hours, minutes, seconds, micro_seconds) => {
+                to_naive_datetime(*year, *month, *day, *hours, *minutes, *seconds, *micro_seconds)
+                    .is_ok_and(|dt| self.time_zone.from_local_datetime(&dt).earliest().is_some())
+            },
+            _ => true,
+        }
+    }
+
+    fn push_sentinel(&mut self) -> Result<()> {
+        self.builder.append_value(0);
+        Ok(())
+    }
+
+    fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
+        if let mysql_async::Value::Date(year, month, day, hours, minutes, seconds, micro_seconds) = value {
+            let dt = to_naive_datetime(year, month, day, hours, minutes, seconds, micro_seconds)?;
+            //During a daylight saving transition a wall clock time can be ambiguous, mysql resolves these to the earlier instant as well
+            let dt = self.time_zone.from_local_datetime(&dt).earliest().with_context(|| format!("Timestamp {dt} does not exist in time zone {:?}.", self.time_zone))?;
+            self.builder.append_value(from_micros(dt.timestamp_micros(), self.unit));
+            Ok(())
+        } else {
+            bail!("Value for column must be Timestamp, got {:?}.", value);
+        }
+    }
+}
+
+fn time_to_micros(is_negative: bool, days: u32, hours: u8, minutes: u8, seconds: u8, micro_seconds: u32) -> i64 {
+    let seconds = ((days as i64 * 24 + hours as i64) * 60 + minutes as i64) * 60 + seconds as i64;
+    let micros = seconds * 1_000_000 + micro_seconds as i64;
+    if is_negative { -micros } else { micros }
+}
+
+//mysql TIME is an interval that can be negative and span up to 838 hours, Duration is the only Arrow type that can hold all of it
+pub struct DurationColumnBuilder {
+    builder: Int64Builder,
+    unit: TimeUnit,
+}
+
+impl DurationColumnBuilder {
+
+    pub fn new(unit: TimeUnit) -> DurationColumnBuilder {
+        DurationColumnBuilder {
+            builder: Int64Builder::new(),
+            unit,
+        }
+    }
+}
+
+impl ColumnBuilder for DurationColumnBuilder {
+
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        let values = self.builder.finish();
+        match self.unit {
+            TimeUnit::Second => Ok(Arc::new(values.reinterpret_cast::<DurationSecondType>())),
+            TimeUnit::Millisecond => Ok(Arc::new(values.reinterpret_cast::<DurationMillisecondType>())),
+            _ => Ok(Arc::new(values.reinterpret_cast::<DurationMicrosecondType>())),
+        }
+    }
+
+    fn push_null(&mut self) -> Result<()> {
+        self.builder.append_null();
+        Ok(())
+    }
+
+    fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
+        if let mysql_async::Value::Time(is_negative, days, hours, minutes, seconds, micro_seconds) = value {
+            let micros = time_to_micros(is_negative, days, hours, minutes, seconds, micro_seconds);
+            self.builder.append_value(from_micros(micros, self.unit));
+            Ok(())
+        } else {
+            bail!("Value for column must be Time, got {:?}.", value);
+        }
+    }
+}
+
+pub struct TimeColumnBuilder {
+    builder: Int64Builder,
+    unit: TimeUnit,
+}
+
+impl TimeColumnBuilder {
+
+    pub fn new(unit: TimeUnit) -> TimeColumnBuilder {
+        TimeColumnBuilder {
+            builder: Int64Builder::new(),
+            unit,
+        }
+    }
+}
+
+impl ColumnBuilder for TimeColumnBuilder {
+
+    fn finish(mut self) -> Result<Arc<dyn Array>> {
+        let values = self.builder.finish();
+        //A time of day fits in 32 bits at second and millisecond precision
+        match self.unit {
+            TimeUnit::Second => Ok(Arc::new(values.unary::<_, Time32SecondType>(|value| value as i32))),
+            TimeUnit::Millisecond => Ok(Arc::new(values.unary::<_, Time32MillisecondType>(|value| value as i32))),
+            _ => Ok(Arc::new(values.reinterpret_cast::<Time64MicrosecondType>())),
+        }
+    }
+
+    fn push_null(&mut self) -> Result<()> {
+        self.builder.append_null();
+        Ok(())
+    }
+
+    fn is_valid(&self, value: &mysql_async::Value) -> bool {
+        match value {
+            mysql_async::Value::Time(is_negative, days, _, _, _, _) => !is_negative && *days == 0,
+            _ => true,
+        }
+    }
+
+    fn push_sentinel(&mut self) -> Result<()> {
+        self.builder.append_value(0);
+        Ok(())
+    }
+
+    fn push_value(&mut self, value: mysql_async::Value) -> Result<()> {
+        if let mysql_async::Value::Time(is_negative, days, hours, minutes, seconds, micro_seconds) = value {
+            if is_negative || days > 0 {
+                bail!("Time value {:?} is outside of the time of day range supported by Time64.", value);
+            }
+            let micros = time_to_micros(is_negative, days, hours, minutes, seconds, micro_seconds);
+            self.builder.append_value(from_micros(micros, self.unit));
+            Ok(())
+        } else {
+            bail!("Value for column must be Time, got {:?}.", value);
+        }
+    }
+}
+
+pub enum Column {
+    String(ColumnHolder<StringColumnBuilder<i32>>),
+    LargeString(ColumnHolder<StringColumnBuilder<i64>>),
+    Binary(ColumnHolder<BinaryColumnBuilder<i32>>),
+    LargeBinary(ColumnHolder<BinaryColumnBuilder<i64>>),
+    ParsedJson(ColumnHolder<ParsedJsonColumnBuilder>),
+    Enum(ColumnHolder<EnumColumnBuilder>),
+    Bit(ColumnHolder<BitColumnBuilder>),
+    FixedBit(ColumnHolder<FixedBitColumnBuilder>),
+    Geometry(ColumnHolder<GeometryColumnBuilder>),
+    Set(ColumnHolder<SetColumnBuilder>),
+    Boolean(ColumnHolder<BooleanColumnBuilder>),
+    Int8(ColumnHolder<IntegerColumnBuilder<Int8Type>>),
+    Int16(ColumnHolder<IntegerColumnBuilder<Int16Type>>),
+    Int32(ColumnHolder<IntegerColumnBuilder<Int32Type>>),
+    Uint8(ColumnHolder<IntegerColumnBuilder<UInt8Type>>),
+    Uint16(ColumnHolder<IntegerColumnBuilder<UInt16Type>>),
+    Uint32(ColumnHolder<IntegerColumnBuilder<UInt32Type>>),
+    Int64(ColumnHolder<IntegerColumnBuilder<Int64Type>>),
+    Uint64(ColumnHolder<IntegerColumnBuilder<UInt64Type>>),
+    Float(ColumnHolder<FloatColumnBuilder>),
+    Double(ColumnHolder<DoubleColumnBuilder>),
+    Decimal(ColumnHolder<DecimalColumnBuilder>),
+    Decimal256(ColumnHolder<Decimal256ColumnBuilder>),
+    Date(ColumnHolder<DateColumnBuilder>),
+    DateTime(ColumnHolder<DateTimeColumnBuilder>),
+    Timestamp(ColumnHolder<TimestampColumnBuilder>),
+    Duration(ColumnHolder<DurationColumnBuilder>),
+    Time(ColumnHolder<TimeColumnBuilder>),
+}
+
+impl Column {
+    pub fn finish(self) -> Result<Arc<dyn Array>> {
+        match self {
+            Column::String(data) => data.finish(),
+            Column::LargeString(data) => data.finish(),
+            Column::Binary(data) => data.finish(),
+            Column::LargeBinary(data) => data.finish(),
+            Column::ParsedJson(data) => data.finish(),
+            Column::Enum(data) => data.finish(),
+            Column::Bit(data) => data.finish(),
+            Column::FixedBit(data) => data.finish(),
+            Column::Geometry(data) => data.finish(),
+            Column::Set(data) => data.finish(),
+            Column::Boolean(data) => data.finish(),
+            Column::Int8(data) => data.finish(),
+            Column::Int16(data) => data.finish(),
+            Column::Int32(data) => data.finish(),
+            Column::Uint8(data) => data.finish(),
+            Column::Uint16(data) => data.finish(),
+            Column::Uint32(data) => data.finish(),
+            Column::Int64(data) => data.finish(),
+            Column::Uint64(data) => data.finish(),
+            Column::Float(data) => data.finish(),
+            Column::Double(data) => data.finish(),
+            Column::Decimal(data) => data.finish(),
+            Column::Decimal256(data) => data.finish(),
+            Column::Date(data) => data.finish(),
+            Column::DateTime(data) => data.finish(),
+            Column::Timestamp(data) => data.finish(),
+            Column::Duration(data) => data.finish(),
+            Column::Time(data) => data.finish(),
+        }
+    }
+
+    pub fn from_data(data: Arc<ColumnData>) -> Result<Column> {
+        Column::with_capacity(data, DEFAULT_CAPACITY)
+    }
+
+    ///Creates a column for chunks of up to capacity rows. Columns can hold more rows, but some are built faster within it.
+    pub fn with_capacity(data: Arc<ColumnData>, capacity: usize) -> Result<Column> {
+        match data.column_type {
+            mysql_column_type::MYSQL_TYPE_TINY
+            | mysql_column_type::MYSQL_TYPE_SHORT
+            | mysql_column_type::MYSQL_TYPE_YEAR
+            | mysql_column_type::MYSQL_TYPE_INT24 => match data.arrow_type {
+                DataType::Boolean => Ok(Column::Boolean(ColumnHolder::new(data, BooleanColumnBuilder::new()))),
+                DataType::Int8 => Ok(Column::Int8(ColumnHolder::new(data, IntegerColumnBuilder::new()))),
+                DataType::Int16 => Ok(Column::Int16(ColumnHolder::new(data, IntegerColumnBuilder::new()))),
+                DataType::Int32 => Ok(Column::Int32(ColumnHolder::new(data, IntegerColumnBuilder::new()))),
+                DataType::UInt8 => Ok(Column::Uint8(ColumnHolder::new(data, IntegerColumnBuilder::new()))),
+                DataType::UInt16 => Ok(Column::Uint16(ColumnHolder::new(data, IntegerColumnBuilder::new()))),
+                DataType::UInt32 => Ok(Column::Uint32(ColumnHolder::new(data, IntegerColumnBuilder::new()))),
+                _ => bail!("Integer column has non-integer Arrow type {:?}.", data.arrow_type),
+            },
+            mysql_column_type::MYSQL_TYPE_LONG | mysql_column_type::MYSQL_TYPE_LONGLONG => {
+                if data.unsigned {
+                let column = Column::Uint64(ColumnHolder::new(data, IntegerColumnBuilder::new()));
+                    Ok(column)
+                } else {
+                let column = Column::Int64(ColumnHolder::new(data, IntegerColumnBuilder::new()));
+                Ok(column)
+                }
+            }
+            mysql_column_type::MYSQL_TYPE_STRING
+            | mysql_column_type::MYSQL_TYPE_VAR_STRING
+            | mysql_column_type::MYSQL_TYPE_VARCHAR
+            | mysql_column_type::MYSQL_TYPE_BLOB
+            | mysql_column_type::MYSQL_TYPE_TINY_BLOB
+            | mysql_column_type::MYSQL_TYPE_MEDIUM_BLOB
+            | mysql_column_type::MYSQL_TYPE_LONG_BLOB
+            | mysql_column_type::MYSQL_TYPE_ENUM
+            | mysql_column_type::MYSQL_TYPE_SET => match data.arrow_type {
+                DataType::Dictionary(_, _) => {
+                    let builder = EnumColumnBuilder::new(data.allowed_values.clone())?;
+                    Ok(Column::Enum(ColumnHolder::new(data, builder)))
+                },
+                DataType::List(_) => {
+                    let builder = SetColumnBuilder::new(data.allowed_values.clone())?;
+                    Ok(Column::Set(ColumnHolder::new(data, builder)))
+                },
+                DataType::Utf8 => Ok(Column::String(ColumnHolder::new(data, StringColumnBuilder::new()))),
+                DataType::LargeUtf8 => Ok(Column::LargeString(ColumnHolder::new(data, StringColumnBuilder::new()))),
+                DataType::Binary => Ok(Column::Binary(ColumnHolder::new(data, BinaryColumnBuilder::new()))),
+                DataType::LargeBinary => Ok(Column::LargeBinary(ColumnHolder::new(data, BinaryColumnBuilder::new()))),
+                _ => bail!("String column has non-string Arrow type {:?}.", data.arrow_type),
+            },
+            mysql_column_type::MYSQL_TYPE_FLOAT => {
+                let column = Column::Float(ColumnHolder::new(data, FloatColumnBuilder::new()));
+                Ok(column)
+            },
+            mysql_column_type::MYSQL_TYPE_DOUBLE => {
+                let column = Column::Double(ColumnHolder::new(data, DoubleColumnBuilder::new()));
+                Ok(column)
+            },
+            mysql_column_type::MYSQL_TYPE_NEWDECIMAL => match data.arrow_type {
+                DataType::Decimal128(precision, scale) => {
+                    let column = Column::Decimal(ColumnHolder::new(data, DecimalColumnBuilder::new(precision, scale)?));
+                    Ok(column)
+                },
+                DataType::Decimal256(precision, scale) => {
+                    let column = Column::Decimal256(ColumnHolder::new(data, Decimal256ColumnBuilder::new(precision, scale)?));
+                    Ok(column)
+                },
+                _ => bail!("Decimal column has non-decimal Arrow type {:?}.", data.arrow_type),
+            },
+            mysql_column_type::MYSQL_TYPE_DATE => {
+                let column = Column::Date(ColumnHolder::new(data, DateColumnBuilder::new()));
+                Ok(column)
+            },
+            mysql_column_type::MYSQL_TYPE_BIT => match data.arrow_type {
+                DataType::FixedSizeBinary(width) => Ok(Column::FixedBit(ColumnHolder::new(data, FixedBitColumnBuilder::new(width)))),
+                _ => Ok(Column::Bit(ColumnHolder::new(data, BitColumnBuilder::new()))),
+            },
+            mysql_column_type::MYSQL_TYPE_GEOMETRY => {
+                let column = Column::Geometry(ColumnHolder::new(data, GeometryColumnBuilder::new()));
+                Ok(column)
+            },
+            mysql_column_type::MYSQL_TYPE_JSON => match data.arrow_type {
+                DataType::Utf8 => Ok(Column::String(ColumnHolder::new(data, StringColumnBuilder::new()))),
+                _ => {
+                    let builder = ParsedJsonColumnBuilder::new(data.arrow_type.clone(), capacity.max(1))?;
+                    Ok(Column::ParsedJson(ColumnHolder::new(data, builder)))
+                },
+            },
+            mysql_column_type::MYSQL_TYPE_DATETIME => match data.arrow_type {
+                DataType::Timestamp(unit, _) => Ok(Column::DateTime(ColumnHolder::new(data, DateTimeColumnBuilder::new(unit)))),
+                _ => bail!("DateTime column has Arrow type {:?}.", data.arrow_type),
+            },
+            mysql_column_type::MYSQL_TYPE_TIMESTAMP => match &data.arrow_type {
+                DataType::Timestamp(unit, Some(time_zone)) => {
+                    let builder = TimestampColumnBuilder::new(*unit, time_zone)?;
+                    Ok(Column::Timestamp(ColumnHolder::new(data, builder)))
+                },
+                _ => bail!("Timestamp column has no time zone in Arrow type {:?}.", data.arrow_type),
+            },
+            mysql_column_type::MYSQL_TYPE_TIME => match data.arrow_type {
+                DataType::Time32(unit) | DataType::Time64(unit) => Ok(Column::Time(ColumnHolder::new(data, TimeColumnBuilder::new(unit)))),
+                DataType::Duration(unit) => Ok(Column::Duration(ColumnHolder::new(data, DurationColumnBuilder::new(unit)))),
+                _ => bail!("Time column has Arrow type {:?}.", data.arrow_type),
+            },
+            _ => bail!("Unsupported column type".to_string()),
+        }
+    }
+
+    pub fn rejects(column: &Column, value: &Value) -> bool {
+        match column {
+            Column::Date(holder) => holder.rejects(value),
+            Column::DateTime(holder) => holder.rejects(value),
+            Column::Timestamp(holder) => holder.rejects(value),
+            Column::Time(holder) => holder.rejects(value),
+            //Only temporal columns have values that can be invalid
+            _ => false,
+        }
+    }
+
+    pub fn push(column: &mut Column, value: Value) -> Result<()> {
+        match column {
+            Column::String(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::LargeString(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Binary(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::LargeBinary(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::ParsedJson(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Enum(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Bit(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::FixedBit(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Geometry(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Set(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Boolean(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Int8(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Int16(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Int32(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Uint8(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Uint16(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Uint32(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Int64(holder) => {
+                holder.push(value)?;
+                Ok(())
+            },
+            Column::Uint64(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Float(holder) => {
+                holder.push(value)?;
+                Ok(())
+            },
+            Column::Double(holder) => {
+                holder.push(value)?;
+                Ok(())
+            },
+            Column::Decimal(holder) => {
+                holder.push(value)?;
+                Ok(())
+            },
+            Column::Decimal256(holder) => {
+                holder.push(value)?;
+                Ok(())
+            },
+            Column::Date(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::DateTime(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Timestamp(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Duration(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+            Column::Time(holder) => {
+                holder.push(value)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(column_type: mysql_column_type, unsigned: bool) -> mysql_async::Column {
+        let flags = if unsigned { ColumnFlags::UNSIGNED_FLAG } else { ColumnFlags::empty() };
+        mysql_async::Column::new(column_type)
+            .with_name(b"testing")
+            .with_flags(flags)
+    }
+
+    fn decimal_column(precision: u32, scale: u8, unsigned: bool) -> mysql_async::Column {
+        //Mirrors how mysql reports the display length of a decimal column
+        let length = precision + if scale > 0 { 1 } else { 0 } + if unsigned { 0 } else { 1 };
+        column(mysql_column_type::MYSQL_TYPE_NEWDECIMAL, unsigned)
+            .with_column_length(length)
+            .with_decimals(scale)
+    }
+
+    mod column_data {
+        use super::*;
+
+        use test_case::test_case;
+
+        macro_rules! type_tests {
+            ($ ($test_function:item)+ ) => {
+                $(
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_VAR_STRING, false), DataType::Utf8; "String type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_STRING, false), DataType::Utf8; "Char type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_VAR_STRING, false).with_character_set(BINARY_CHARACTER_SET), DataType::Binary; "Varbinary type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_STRING, false).with_character_set(BINARY_CHARACTER_SET), DataType::Binary; "Binary type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_BLOB, false).with_column_length(262_140), DataType::Utf8; "Text type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_BLOB, false).with_column_length(65_535).with_character_set(BINARY_CHARACTER_SET), DataType::Binary; "Blob type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_BLOB, false).with_column_length(u32::MAX), DataType::LargeUtf8; "Long text type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_BLOB, false).with_column_length(16_777_215).with_character_set(BINARY_CHARACTER_SET), DataType::LargeBinary; "Medium blob type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_TINY_BLOB, false).with_character_set(BINARY_CHARACTER_SET), DataType::Binary; "Tiny blob type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_MEDIUM_BLOB, false), DataType::LargeUtf8; "Medium blob type code")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_LONG_BLOB, false).with_character_set(BINARY_CHARACTER_SET), DataType::LargeBinary; "Long blob type code")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_TINY, true), DataType::UInt8; "Unsigned tiny column type")]
+                #[test_case(column(mysql_column_type::MYSQL_TYPE_TINY, false), DataType::Int8; "Signed tiny column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_TINY, false).with_column_length(1), DataType::Int8; "Tinyint(1) column type without boolean option")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_SHORT, true), DataType::UInt16; "Unsigned short column type")]
+                #[test_case(column(mysql_column_type::MYSQL_TYPE_SHORT, false), DataType::Int16; "Signed short column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_INT24, true), DataType::UInt32; "Unsigned medium column type")]
+                #[test_case(column(mysql_column_type::MYSQL_TYPE_INT24, false), DataType::Int32; "Signed medium column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_YEAR, true), DataType::UInt16; "Year column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_LONG, true), DataType::UInt64; "Unsigned Long column type")]
+                #[test_case(column(mysql_column_type::MYSQL_TYPE_LONG, false), DataType::Int64; "Signed Long column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_LONGLONG, true), DataType::UInt64; "Unsigned long long column type")]
+                #[test_case(column(mysql_column_type::MYSQL_TYPE_LONGLONG, false), DataType::Int64; "Signed long long column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_FLOAT, false), DataType::Float32; "Float column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_DOUBLE, false), DataType::Float64; "Double column type")]
+        #[test_case(decimal_column(19, 2, false), DataType::Decimal128(19, 2); "Signed decimal column type")]
+        #[test_case(decimal_column(19, 2, true), DataType::Decimal128(19, 2); "Unsigned decimal column type")]
+        #[test_case(decimal_column(38, 8, false), DataType::Decimal128(38, 8); "Max precision decimal128 column type")]
+        #[test_case(decimal_column(10, 0, false), DataType::Decimal128(10, 0); "Zero scale decimal column type")]
+        #[test_case(decimal_column(65, 30, false), DataType::Decimal256(65, 30); "Decimal256 column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_DATE, false), DataType::Date32; "Date column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_BIT, true).with_column_length(1), DataType::UInt64; "Bit column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_BIT, true).with_column_length(64), DataType::UInt64; "Bit 64 column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_BIT, true).with_column_length(72), DataType::FixedSizeBinary(9); "Wide bit column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_DATETIME, false), DataType::Timestamp(TimeUnit::Second, None); "DateTime column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_DATETIME, false).with_decimals(3), DataType::Timestamp(TimeUnit::Millisecond, None); "DateTime(3) column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_DATETIME, false).with_decimals(6), DataType::Timestamp(TimeUnit::Microsecond, None); "DateTime(6) column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_TIME, false), DataType::Duration(TimeUnit::Second); "Time column type")]
+        #[test_case(column(mysql_column_type::MYSQL_TYPE_TIME, false).with_decimals(2), DataType::Duration(TimeUnit::Millisecond); "Time(2) column type")]
+        
+        $test_function
+                )+
+            }
+        }
+
+        type_tests! {
+            fn get_arrow_type_known(column: mysql_async::Column, expected: DataType) {
+                assert_eq!(ColumnData::get_arrow_type(&column, &ColumnOptions::default()).unwrap(), expected);
+            }
+        }
 
         #[test]
         #[should_panic]
         fn get_arrow_type_unknown() {
-            ColumnData::get_arrow_type(mysql_column_type::MYSQL_TYPE_BIT, false).unwrap();
+            ColumnData::get_arrow_type(&column(mysql_column_type::MYSQL_TYPE_VECTOR, false), &ColumnOptions::default()).unwrap();
         }
 
         type_tests! {
-            fn new_known_types(column_type: mysql_column_type, unsigned: bool, expected_arrow_type: DataType) {
+            fn new_known_types(column: mysql_async::Column, expected_arrow_type: DataType) {
                 let test_data = ColumnData {
                     name: String::from("testing"),
-                    unsigned: unsigned,
+                    unsigned: column.flags().contains(ColumnFlags::UNSIGNED_FLAG),
                     nullable: true,
-                    column_type: column_type,
+                    flags: column.flags(),
+                    column_type: column.column_type(),
+                    column_length: column.column_length(),
+                    decimals: column.decimals(),
+                    character_set: column.character_set(),
+                    invalid_value_policy: InvalidValuePolicy::Fail,
+                    allowed_values: None,
+                    srid: None,
+                    definition: None,
                     arrow_type: expected_arrow_type,
                 };
-                let data = ColumnData::new(
-                    String::from("testing"),
-                    unsigned,
-                    true,
-                    column_type,
-                )
-                .unwrap();
+                let data = ColumnData::new(&column, &ColumnOptions::default()).unwrap();
                 assert_eq!(data, test_data);
             }
         }
@@ -487,28 +1788,577 @@ mod tests {
         #[test]
         #[should_panic]
         fn new_unknown_types() {
-            ColumnData::new(
-                String::from("testing"),
-                false,
-                true,
-                mysql_column_type::MYSQL_TYPE_BIT,
-            )
-            .unwrap();
+            ColumnData::new(&column(mysql_column_type::MYSQL_TYPE_VECTOR, false), &ColumnOptions::default()).unwrap();
         }
 
         type_tests! {
-            fn get_schema_field(column_type: mysql_column_type, unsigned: bool, expected_arrow_type: DataType) {
+            fn get_schema_field(column: mysql_async::Column, expected_arrow_type: DataType) {
                 let test_field = Field::new(String::from("testing"), expected_arrow_type, true);
-                let data = ColumnData::new(
-                    String::from("testing"),
-                    unsigned,
-                    true,
-                column_type
-                )
-                .unwrap();
+                let data = ColumnData::new(&column, &ColumnOptions::default()).unwrap();
                 let field = data.get_schema_field();
-                assert_eq!(field, test_field);
+                assert_eq!(field.with_metadata(HashMap::new()), test_field);
+            }
+        }
+    }
+
+    mod source_metadata {
+        use super::*;
+
+        #[test]
+        fn protocol_metadata() {
+            let column = decimal_column(10, 2, true)
+                .with_flags(ColumnFlags::UNSIGNED_FLAG | ColumnFlags::NOT_NULL_FLAG | ColumnFlags::PRI_KEY_FLAG)
+                .with_character_set(BINARY_CHARACTER_SET);
+            let field = ColumnData::new(&column, &ColumnOptions::default()).unwrap().get_schema_field();
+            let metadata = field.metadata();
+            assert_eq!(metadata["mysql.type"], "MYSQL_TYPE_NEWDECIMAL");
+            assert_eq!(metadata["mysql.length"], "11");
+            assert_eq!(metadata["mysql.decimals"], "2");
+            assert_eq!(metadata["mysql.collation_id"], "63");
+            assert_eq!(metadata["mysql.unsigned"], "true");
+            assert_eq!(metadata["mysql.nullable"], "false");
+            assert_eq!(metadata["mysql.primary_key"], "true");
+            assert_eq!(metadata["mysql.unique_key"], "false");
+            assert_eq!(metadata["mysql.auto_increment"], "false");
+            assert!(!metadata.contains_key("mysql.column_type"));
+        }
+
+        #[test]
+        fn source_nullability_survives_null_policy() {
+            let column = column(mysql_column_type::MYSQL_TYPE_DATE, false).with_flags(ColumnFlags::NOT_NULL_FLAG);
+            let options = ColumnOptions { invalid_value_policy: InvalidValuePolicy::Null, ..Default::default() };
+            let field = ColumnData::new(&column, &options).unwrap().get_schema_field();
+            assert!(field.is_nullable());
+            assert_eq!(field.metadata()["mysql.nullable"], "false");
+        }
+
+        #[test]
+        fn definition_metadata() {
+            let definition = ColumnDefinition {
+                column_type: String::from("varchar(64)"),
+                character_set: Some(String::from("utf8mb4")),
+                collation: Some(String::from("utf8mb4_0900_ai_ci")),
+                default: None,
+                key: String::from("UNI"),
+                extra: String::new(),
+                comment: String::from("Login name"),
+            };
+            let options = ColumnOptions {
+                column_definitions: HashMap::from([(String::from("testing"), definition)]),
+                ..Default::default()
+            };
+            let field = ColumnData::new(&column(mysql_column_type::MYSQL_TYPE_VAR_STRING, false), &options)
+                .unwrap()
+                .get_schema_field();
+            let metadata = field.metadata();
+            assert_eq!(metadata["mysql.column_type"], "varchar(64)");
+            assert_eq!(metadata["mysql.character_set"], "utf8mb4");
+            assert_eq!(metadata["mysql.collation"], "utf8mb4_0900_ai_ci");
+            assert_eq!(metadata["mysql.key"], "UNI");
+            assert_eq!(metadata["mysql.extra"], "");
+            assert_eq!(metadata["mysql.comment"], "Login name");
+            assert!(!metadata.contains_key("mysql.default"));
+        }
+    }
+
+    mod integer {
+        use super::*;
+
+        use test_case::test_case;
+
+        fn push_values(column: mysql_async::Column, options: &ColumnOptions, values: Vec<Value>) -> Result<Arc<dyn Array>> {
+            let data = Arc::new(ColumnData::new(&column, options)?);
+            let mut column = Column::from_data(data)?;
+            for value in values {
+                Column::push(&mut column, value)?;
+            }
+            column.finish()
+        }
+
+        #[test]
+        fn tinyint1_as_boolean() {
+            let options = ColumnOptions { tinyint1_as_boolean: true, ..Default::default() };
+            let column = column(mysql_column_type::MYSQL_TYPE_TINY, false).with_column_length(1);
+            let array = push_values(column, &options, vec![Value::Int(0), Value::Int(1), Value::Int(2)]).unwrap();
+            let array = array.as_any().downcast_ref::<arrow::array::BooleanArray>().unwrap();
+            assert_eq!(array.iter().collect::<Vec<_>>(), vec![Some(false), Some(true), Some(true)]);
+        }
+
+        #[test]
+        fn wide_tinyint_not_boolean() {
+            let options = ColumnOptions { tinyint1_as_boolean: true, ..Default::default() };
+            let column = column(mysql_column_type::MYSQL_TYPE_TINY, false).with_column_length(4);
+            assert_eq!(ColumnData::get_arrow_type(&column, &options).unwrap(), DataType::Int8);
+        }
+
+        #[test_case(mysql_column_type::MYSQL_TYPE_TINY, false, i8::MIN as i64, i8::MAX as i64; "Signed tiny")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_TINY, true, 0, u8::MAX as i64; "Unsigned tiny")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_SHORT, false, i16::MIN as i64, i16::MAX as i64; "Signed short")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_SHORT, true, 0, u16::MAX as i64; "Unsigned short")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_INT24, false, i32::MIN as i64, i32::MAX as i64; "Signed medium")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_INT24, true, 0, u32::MAX as i64; "Unsigned medium")]
+        fn range_boundaries(column_type: mysql_column_type, unsigned: bool, min: i64, max: i64) {
+            let options = ColumnOptions::default();
+            let array = push_values(column(column_type, unsigned), &options, vec![Value::Int(min), Value::Int(max)]).unwrap();
+            assert_eq!(array.len(), 2);
+            assert!(push_values(column(column_type, unsigned), &options, vec![Value::Int(min - 1)]).is_err());
+            assert!(push_values(column(column_type, unsigned), &options, vec![Value::Int(max + 1)]).is_err());
+        }
+
+        #[test]
+        fn signed_bigint_boundaries() {
+            let options = ColumnOptions::default();
+            let column = column(mysql_column_type::MYSQL_TYPE_LONGLONG, false);
+            let array = push_values(column.clone(), &options, vec![Value::Int(i64::MIN), Value::Int(i64::MAX), Value::UInt(i64::MAX as u64)]).unwrap();
+            let array = array.as_any().downcast_ref::<arrow::array::Int64Array>().unwrap();
+            assert_eq!(array.values().to_vec(), vec![i64::MIN, i64::MAX, i64::MAX]);
+            assert!(push_values(column, &options, vec![Value::UInt(i64::MAX as u64 + 1)]).is_err());
+        }
+
+        #[test]
+        fn unsigned_bigint_boundaries() {
+            let options = ColumnOptions::default();
+            let column = column(mysql_column_type::MYSQL_TYPE_LONGLONG, true);
+            let array = push_values(column.clone(), &options, vec![Value::Int(0), Value::Int(i64::MAX), Value::UInt(i64::MAX as u64 + 1), Value::UInt(u64::MAX)]).unwrap();
+            let array = array.as_any().downcast_ref::<arrow::array::UInt64Array>().unwrap();
+            assert_eq!(array.values().to_vec(), vec![0, i64::MAX as u64, i64::MAX as u64 + 1, u64::MAX]);
+            assert!(push_values(column, &options, vec![Value::Int(-1)]).is_err());
+        }
+
+        #[test]
+        fn double_values() {
+            let options = ColumnOptions::default();
+            let column = column(mysql_column_type::MYSQL_TYPE_DOUBLE, false);
+            let array = push_values(column.clone(), &options, vec![Value::Double(f64::MIN), Value::Double(f64::MAX)]).unwrap();
+            let array = array.as_any().downcast_ref::<arrow::array::Float64Array>().unwrap();
+            assert_eq!(array.values().to_vec(), vec![f64::MIN, f64::MAX]);
+            assert!(push_values(column, &options, vec![Value::Float(1.0)]).is_err());
+        }
+    }
+
+    mod string {
+        use super::*;
+
+        #[test]
+        fn invalid_utf8_is_error() {
+            let data = Arc::new(ColumnData::new(&column(mysql_column_type::MYSQL_TYPE_VAR_STRING, false), &ColumnOptions::default()).unwrap());
+            let mut column = Column::from_data(data).unwrap();
+            assert!(Column::push(&mut column, Value::Bytes(vec![0xff, 0xfe])).is_err());
+        }
+
+        #[test]
+        fn binary_keeps_bytes() {
+            let column = column(mysql_column_type::MYSQL_TYPE_BLOB, false).with_character_set(BINARY_CHARACTER_SET);
+            let data = Arc::new(ColumnData::new(&column, &ColumnOptions::default()).unwrap());
+            let mut column = Column::from_data(data).unwrap();
+            Column::push(&mut column, Value::Bytes(vec![0xff, 0xfe])).unwrap();
+            let array = column.finish().unwrap();
+            let array = array.as_any().downcast_ref::<arrow::array::BinaryArray>().unwrap();
+            assert_eq!(array.value(0), &[0xff, 0xfe]);
+        }
+    }
+
+    mod temporal {
+        use super::*;
+
+        fn options(time_zone: &str) -> ColumnOptions {
+            ColumnOptions { time_zone: Some(time_zone.to_string()), ..Default::default() }
+        }
+
+        fn timestamp_micros(time_zone: &str, value: Value) -> i64 {
+            let column = column(mysql_column_type::MYSQL_TYPE_TIMESTAMP, false).with_decimals(6);
+            let data = Arc::new(ColumnData::new(&column, &options(time_zone)).unwrap());
+            let mut column = Column::from_data(data).unwrap();
+            Column::push(&mut column, value).unwrap();
+            let array = column.finish().unwrap();
+            assert_eq!(array.data_type(), &DataType::Timestamp(TimeUnit::Microsecond, Some(time_zone.into())));
+            array.as_any().downcast_ref::<arrow::array::TimestampMicrosecondArray>().unwrap().value(0)
+        }
+
+        #[test]
+        fn timestamp_requires_time_zone() {
+            let column = column(mysql_column_type::MYSQL_TYPE_TIMESTAMP, false);
+            assert!(ColumnData::get_arrow_type(&column, &ColumnOptions::default()).is_err());
+            assert!(ColumnData::get_arrow_type(&column, &options("Not/AZone")).is_err());
+        }
+
+        #[test]
+        fn timestamp_converted_to_utc() {
+            let utc = timestamp_micros("+00:00", Value::Date(2024, 1, 15, 12, 0, 0, 500));
+            assert_eq!(timestamp_micros("+02:00", Value::Date(2024, 1, 15, 14, 0, 0, 500)), utc);
+            //Europe/Berlin is UTC+1 in January
+            assert_eq!(timestamp_micros("Europe/Berlin", Value::Date(2024, 1, 15, 13, 0, 0, 500)), utc);
+        }
+
+        #[test]
+        fn time_as_duration() {
+            let column = column(mysql_column_type::MYSQL_TYPE_TIME, false).with_decimals(6);
+            let data = Arc::new(ColumnData::new(&column, &ColumnOptions::default()).unwrap());
+            let mut column = Column::from_data(data).unwrap();
+            Column::push(&mut column, Value::Time(true, 1, 10, 0, 0, 0)).unwrap();
+            Column::push(&mut column, Value::Time(false, 0, 0, 0, 1, 250)).unwrap();
+            let array = column.finish().unwrap();
+            let array = array.as_any().downcast_ref::<arrow::array::DurationMicrosecondArray>().unwrap();
+            assert_eq!(array.values().to_vec(), vec![-34 * 3_600_000_000, 1_000_250]);
+        }
+
+        #[test]
+        fn time_as_time64() {
+            let options = ColumnOptions { time_as_time64: true, ..Default::default() };
+            let data = Arc::new(ColumnData::new(&column(mysql_column_type::MYSQL_TYPE_TIME, false).with_decimals(6), &options).unwrap());
+            assert_eq!(data.get_schema_field().data_type(), &DataType::Time64(TimeUnit::Microsecond));
+            let mut column = Column::from_data(data).unwrap();
+            Column::push(&mut column, Value::Time(false, 0, 23, 59, 59, 999_999)).unwrap();
+            assert!(Column::push(&mut column, Value::Time(false, 1, 0, 0, 0, 0)).is_err());
+            assert!(Column::push(&mut column, Value::Time(true, 0, 1, 0, 0, 0)).is_err());
+        }
+
+        fn push_value(column: mysql_async::Column, options: &ColumnOptions, value: Value) -> Arc<dyn Array> {
+            let data = Arc::new(ColumnData::new(&column, options).unwrap());
+            let mut column = Column::from_data(data).unwrap();
+            Column::push(&mut column, value).unwrap();
+            column.finish().unwrap()
+        }
+
+        #[test]
+        fn precision_zero_uses_seconds() {
+            let datetime = column(mysql_column_type::MYSQL_TYPE_DATETIME, false);
+            let array = push_value(datetime, &ColumnOptions::default(), Value::Date(1969, 12, 31, 23, 59, 59, 0));
+            assert_eq!(array.as_any().downcast_ref::<arrow::array::TimestampSecondArray>().unwrap().value(0), -1);
+
+            let timestamp = column(mysql_column_type::MYSQL_TYPE_TIMESTAMP, false);
+            let array = push_value(timestamp, &options("+01:00"), Value::Date(1970, 1, 1, 1, 0, 5, 0));
+            assert_eq!(array.data_type(), &DataType::Timestamp(TimeUnit::Second, Some("+01:00".into())));
+            assert_eq!(array.as_any().downcast_ref::<arrow::array::TimestampSecondArray>().unwrap().value(0), 5);
+
+            let time = column(mysql_column_type::MYSQL_TYPE_TIME, false);
+            let array = push_value(time.clone(), &ColumnOptions::default(), Value::Time(true, 1, 2, 0, 0, 0));
+            assert_eq!(array.as_any().downcast_ref::<arrow::array::DurationSecondArray>().unwrap().value(0), -26 * 3600);
+            let options = ColumnOptions { time_as_time64: true, ..Default::default() };
+            let array = push_value(time, &options, Value::Time(false, 0, 1, 0, 1, 0));
+            assert_eq!(array.as_any().downcast_ref::<arrow::array::Time32SecondArray>().unwrap().value(0), 3601);
+        }
+
+        #[test]
+        fn precision_three_uses_milliseconds() {
+            let datetime = column(mysql_column_type::MYSQL_TYPE_DATETIME, false).with_decimals(3);
+            let array = push_value(datetime, &ColumnOptions::default(), Value::Date(1970, 1, 1, 0, 0, 1, 250_000));
+            assert_eq!(array.as_any().downcast_ref::<arrow::array::TimestampMillisecondArray>().unwrap().value(0), 1_250);
+
+            let timestamp = column(mysql_column_type::MYSQL_TYPE_TIMESTAMP, false).with_decimals(3);
+            let array = push_value(timestamp, &options("+00:00"), Value::Date(1970, 1, 1, 0, 0, 2, 500_000));
+            assert_eq!(array.data_type(), &DataType::Timestamp(TimeUnit::Millisecond, Some("+00:00".into())));
+
+            let time = column(mysql_column_type::MYSQL_TYPE_TIME, false).with_decimals(3);
+            let array = push_value(time.clone(), &ColumnOptions::default(), Value::Time(false, 0, 0, 0, 1, 5_000));
+            assert_eq!(array.as_any().downcast_ref::<arrow::array::DurationMillisecondArray>().unwrap().value(0), 1_005);
+            let options = ColumnOptions { time_as_time64: true, ..Default::default() };
+            let array = push_value(time, &options, Value::Time(false, 0, 0, 0, 1, 5_000));
+            assert_eq!(array.data_type(), &DataType::Time32(TimeUnit::Millisecond));
+            assert_eq!(array.as_any().downcast_ref::<arrow::array::Time32MillisecondArray>().unwrap().value(0), 1_005);
+        }
+    }
+
+    mod invalid_value_policy {
+        use super::*;
+
+        use test_case::test_case;
+
+        const ZERO_DATE: Value = Value::Date(0, 0, 0, 0, 0, 0, 0);
+
+        fn not_null_column(column_type: mysql_column_type) -> mysql_async::Column {
+            column(column_type, false).with_flags(ColumnFlags::NOT_NULL_FLAG)
+        }
+
+        fn options(policy: InvalidValuePolicy) -> ColumnOptions {
+            ColumnOptions {
+                invalid_value_policy: policy,
+                time_zone: Some("+00:00".to_string()),
+                time_as_time64: true,
+                ..Default::default()
+            }
+        }
+
+        #[test_case(mysql_column_type::MYSQL_TYPE_DATE, ZERO_DATE; "Zero date")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_DATE, Value::Date(2023, 2, 30, 0, 0, 0, 0); "Invalid day")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_DATETIME, ZERO_DATE; "Zero datetime")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_TIMESTAMP, ZERO_DATE; "Zero timestamp")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_TIME, Value::Time(true, 0, 1, 0, 0, 0); "Negative time of day")]
+        fn fail(column_type: mysql_column_type, value: Value) {
+            let data = Arc::new(ColumnData::new(&not_null_column(column_type), &options(InvalidValuePolicy::Fail)).unwrap());
+            let mut column = Column::from_data(data).unwrap();
+            assert!(!Column::rejects(&column, &value));
+            assert!(Column::push(&mut column, value).is_err());
+        }
+
+        #[test_case(mysql_column_type::MYSQL_TYPE_DATE, ZERO_DATE; "Zero date")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_DATETIME, ZERO_DATE; "Zero datetime")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_TIMESTAMP, ZERO_DATE; "Zero timestamp")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_TIME, Value::Time(false, 2, 0, 0, 0, 0); "Time beyond a day")]
+        fn null(column_type: mysql_column_type, value: Value) {
+            let data = Arc::new(ColumnData::new(&not_null_column(column_type), &options(InvalidValuePolicy::Null)).unwrap());
+            assert!(data.get_schema_field().is_nullable());
+            let mut column = Column::from_data(data).unwrap();
+            Column::push(&mut column, value).unwrap();
+            assert!(column.finish().unwrap().is_null(0));
+        }
+
+        #[test_case(mysql_column_type::MYSQL_TYPE_DATE, ZERO_DATE; "Zero date")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_DATETIME, ZERO_DATE; "Zero datetime")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_TIMESTAMP, ZERO_DATE; "Zero timestamp")]
+        #[test_case(mysql_column_type::MYSQL_TYPE_TIME, Value::Time(true, 0, 1, 0, 0, 0); "Negative time of day")]
+        fn sentinel(column_type: mysql_column_type, value: Value) {
+            let data = Arc::new(ColumnData::new(&not_null_column(column_type), &options(InvalidValuePolicy::Sentinel)).unwrap());
+            assert!(!data.get_schema_field().is_nullable());
+            let mut column = Column::from_data(data).unwrap();
+            Column::push(&mut column, value).unwrap();
+            let array = column.finish().unwrap();
+            assert!(!array.is_null(0));
+            //The sentinel is zero in whichever unit and width the column uses
+            assert!(array.to_data().buffers()[0].as_slice().iter().all(|byte| *byte == 0));
+        }
+
+        #[test]
+        fn reject() {
+            let data = Arc::new(ColumnData::new(&not_null_column(mysql_column_type::MYSQL_TYPE_DATE), &options(InvalidValuePolicy::Reject)).unwrap());
+            let column = Column::from_data(data).unwrap();
+            assert!(Column::rejects(&column, &ZERO_DATE));
+            assert!(!Column::rejects(&column, &Value::Date(2024, 1, 1, 0, 0, 0, 0)));
+        }
+
+        #[test]
+        fn column_override() {
+            let mut options = options(InvalidValuePolicy::Fail);
+            options.column_policies.insert("testing".to_string(), InvalidValuePolicy::Null);
+            let data = Arc::new(ColumnData::new(&not_null_column(mysql_column_type::MYSQL_TYPE_DATE), &options).unwrap());
+            let mut column = Column::from_data(data).unwrap();
+            Column::push(&mut column, ZERO_DATE).unwrap();
+            assert_eq!(options.get_invalid_value_policy("other"), InvalidValuePolicy::Fail);
+        }
+    }
+
+    mod bits_and_geometry {
+        use super::*;
+
+        use arrow::array::AsArray;
+
+        fn push_values(column: mysql_async::Column, options: &ColumnOptions, values: Vec<Value>) -> Arc<dyn Array> {
+            let data = Arc::new(ColumnData::new(&column, options).unwrap());
+            let mut column = Column::from_data(data).unwrap();
+            for value in values {
+                Column::push(&mut column, value).unwrap();
+            }
+            column.finish().unwrap()
+        }
+
+        #[test]
+        fn bit_values() {
+            let column = column(mysql_column_type::MYSQL_TYPE_BIT, true).with_column_length(12);
+            let array = push_values(column, &ColumnOptions::default(), vec![Value::Bytes(vec![0x0f, 0xff]), Value::Bytes(vec![0x01])]);
+            assert_eq!(array.as_primitive::<UInt64Type>().values().to_vec(), vec![0x0fff, 1]);
+        }
+
+        #[test]
+        fn wide_bit_values() {
+            let column = column(mysql_column_type::MYSQL_TYPE_BIT, true).with_column_length(72);
+            let array = push_values(column, &ColumnOptions::default(), vec![Value::Bytes(vec![0x01, 0x02])]);
+            assert_eq!(array.as_fixed_size_binary().value(0), &[0, 0, 0, 0, 0, 0, 0, 0x01, 0x02]);
+        }
+
+        #[test]
+        fn geometry_strips_srid() {
+            //POINT(1 2) with SRID 4326
+            let mut value = 4326u32.to_le_bytes().to_vec();
+            let wkb = [vec![1u8], 1u32.to_le_bytes().to_vec(), 1f64.to_le_bytes().to_vec(), 2f64.to_le_bytes().to_vec()].concat();
+            value.extend_from_slice(&wkb);
+            let column = column(mysql_column_type::MYSQL_TYPE_GEOMETRY, false).with_character_set(BINARY_CHARACTER_SET);
+            let array = push_values(column, &ColumnOptions::default(), vec![Value::Bytes(value)]);
+            assert_eq!(array.as_binary::<i32>().value(0), wkb.as_slice());
+        }
+
+        #[test]
+        fn geometry_invalid() {
+            let data = Arc::new(ColumnData::new(&column(mysql_column_type::MYSQL_TYPE_GEOMETRY, false), &ColumnOptions::default()).unwrap());
+            let mut column = Column::from_data(data).unwrap();
+            assert!(Column::push(&mut column, Value::Bytes(vec![0, 0, 0])).is_err());
+        }
+
+        #[test]
+        fn geo_metadata() {
+            let mut options = ColumnOptions::default();
+            options.geometry_srids.insert("testing".to_string(), 4326);
+            let data = Arc::new(ColumnData::new(&column(mysql_column_type::MYSQL_TYPE_GEOMETRY, false), &options).unwrap());
+            assert_eq!(data.get_schema_field().extension_type_name(), Some(WKB_EXTENSION_NAME));
+            let metadata: serde_json::Value = serde_json::from_str(&ColumnData::get_geo_metadata(&[data]).unwrap()).unwrap();
+            assert_eq!(metadata["primary_column"], "testing");
+            assert_eq!(metadata["columns"]["testing"]["encoding"], "WKB");
+            assert_eq!(metadata["columns"]["testing"]["crs"]["id"]["code"], 4326);
+
+            let data = Arc::new(ColumnData::new(&column(mysql_column_type::MYSQL_TYPE_LONG, false), &options).unwrap());
+            assert_eq!(ColumnData::get_geo_metadata(&[data]), None);
+        }
+    }
+
+    mod enums {
+        use super::*;
+
+        use arrow::array::AsArray;
+        use test_case::test_case;
+
+        fn options() -> ColumnOptions {
+            let mut options = ColumnOptions::default();
+            options.enum_values.insert("testing".to_string(), vec!["low".to_string(), "high".to_string()]);
+            options
+        }
+
+        #[test_case("enum('low','high')", vec!["low", "high"]; "Enum values")]
+        #[test_case("set('a','b,c','it''s')", vec!["a", "b,c", "it's"]; "Set values with commas and quotes")]
+        #[test_case("enum('')", vec![""]; "Empty value")]
+        fn parse_allowed_values(column_type: &str, expected: Vec<&str>) {
+            assert_eq!(ColumnData::parse_allowed_values(column_type).unwrap(), expected);
+        }
+
+        #[test]
+        fn parse_allowed_values_invalid() {
+            assert!(ColumnData::parse_allowed_values("enum('low").is_err());
+            assert!(ColumnData::parse_allowed_values("int").is_err());
+        }
+
+        #[test]
+        fn enum_dictionary() {
+            let column = column(mysql_column_type::MYSQL_TYPE_STRING, false).with_flags(ColumnFlags::ENUM_FLAG);
+            let data = Arc::new(ColumnData::new(&column, &options()).unwrap());
+            let field = data.get_schema_field();
+            assert_eq!(field.data_type(), &ColumnData::get_enum_type());
+            assert_eq!(field.metadata().get(ALLOWED_VALUES_KEY).unwrap(), r#"["low","high"]"#);
+            let mut column = Column::from_data(data).unwrap();
+            Column::push(&mut column, Value::Bytes(b"high".to_vec())).unwrap();
+            assert!(Column::push(&mut column, Value::Bytes(b"medium".to_vec())).is_err());
+            let array = column.finish().unwrap();
+            let array = array.as_dictionary::<Int16Type>();
+            assert_eq!(array.keys().value(0), 1);
+            assert_eq!(array.values().len(), 2);
+        }
+
+        #[test]
+        fn set_list() {
+            let column = column(mysql_column_type::MYSQL_TYPE_STRING, false).with_flags(ColumnFlags::SET_FLAG);
+            let data = Arc::new(ColumnData::new(&column, &options()).unwrap());
+            let mut column = Column::from_data(data).unwrap();
+            Column::push(&mut column, Value::Bytes(b"low,high".to_vec())).unwrap();
+            Column::push(&mut column, Value::Bytes(b"".to_vec())).unwrap();
+            assert!(Column::push(&mut column, Value::Bytes(b"low,medium".to_vec())).is_err());
+            let array = column.finish().unwrap();
+            let array = array.as_list::<i32>();
+            assert_eq!(array.value_length(0), 2);
+            assert_eq!(array.value_length(1), 0);
+        }
+    }
+
+    mod json {
+        use super::*;
+
+        use arrow::array::AsArray;
+
+        fn json_column() -> mysql_async::Column {
+            column(mysql_column_type::MYSQL_TYPE_JSON, false).with_character_set(BINARY_CHARACTER_SET)
+        }
+
+        #[test]
+        fn text_with_extension_metadata() {
+            let data = ColumnData::new(&json_column(), &ColumnOptions::default()).unwrap();
+            let field = data.get_schema_field();
+            assert_eq!(field.data_type(), &DataType::Utf8);
+            assert_eq!(field.extension_type_name(), Some(JSON_EXTENSION_NAME));
+        }
+
+        #[test]
+        fn infer_nested_type() {
+            let values: Vec<&[u8]> = vec![br#"{"theme": "dark", "alerts": [1, 2]}"#, br#"{"theme": "light", "alerts": []}"#];
+            let data_type = ColumnData::infer_json_type(values.into_iter()).unwrap().unwrap();
+            let DataType::Struct(fields) = &data_type else { panic!("Expected struct, got {data_type:?}") };
+            assert_eq!(fields.find("theme").unwrap().1.data_type(), &DataType::Utf8);
+            assert!(matches!(fields.find("alerts").unwrap().1.data_type(), DataType::List(_)));
+            assert_eq!(ColumnData::infer_json_type(std::iter::empty()).unwrap(), None);
+        }
+
+        #[test]
+        fn parse_into_inferred_type() {
+            let values: Vec<&[u8]> = vec![br#"{"theme": "dark", "alerts": [1, 2]}"#];
+            let data_type = ColumnData::infer_json_type(values.into_iter()).unwrap().unwrap();
+            let mut options = ColumnOptions::default();
+            options.json_types.insert("testing".to_string(), data_type.clone());
+            let data = Arc::new(ColumnData::new(&json_column(), &options).unwrap());
+            assert_eq!(data.get_schema_field().extension_type_name(), None);
+            let mut column = Column::from_data(data).unwrap();
+            Column::push(&mut column, Value::Bytes(br#"{"theme": "light", "alerts": [3]}"#.to_vec())).unwrap();
+            Column::push(&mut column, Value::NULL).unwrap();
+            let array = column.finish().unwrap();
+            assert_eq!(array.data_type(), &data_type);
+            assert_eq!(array.len(), 2);
+            assert!(array.is_null(1));
+            let themes = array.as_struct().column_by_name("theme").unwrap().as_string::<i32>().value(0).to_string();
+            assert_eq!(themes, "light");
+        }
+
+        fn parsed_column(capacity: usize) -> Column {
+            let mut options = ColumnOptions::default();
+            options.json_types.insert("testing".to_string(), DataType::Int64);
+            Column::with_capacity(Arc::new(ColumnData::new(&json_column(), &options).unwrap()), capacity).unwrap()
+        }
+
+        #[test]
+        fn mismatched_values_fail_chunk() {
+            let mut column = parsed_column(10);
+            Column::push(&mut column, Value::Bytes(b"1".to_vec())).unwrap();
+            Column::push(&mut column, Value::Bytes(br#"{"unknown": 1}"#.to_vec())).unwrap();
+            assert!(column.finish().is_err());
+
+            let mut column = parsed_column(10);
+            assert!(Column::push(&mut column, Value::Bytes(b"1} {\"value\": 2".to_vec())).is_err());
+            assert!(Column::push(&mut column, Value::Bytes(b"[1".to_vec())).is_err());
+        }
+
+        #[test]
+        fn chunks_beyond_capacity() {
+            let mut column = parsed_column(2);
+            for value in 0..5 {
+                Column::push(&mut column, Value::Bytes(value.to_string().into_bytes())).unwrap();
             }
+            let array = column.finish().unwrap();
+            assert_eq!(array.as_primitive::<Int64Type>().values().to_vec(), vec![0, 1, 2, 3, 4]);
+            assert_eq!(parsed_column(2).finish().unwrap().len(), 0);
+        }
+    }
+
+    mod decimal {
+        use super::*;
+
+        use test_case::test_case;
+
+        #[test_case("123.45", 19, 2, 12345; "Exact scale")]
+        #[test_case("-0.5", 19, 2, -50; "Negative value padded to scale")]
+        #[test_case("1.23450000", 19, 4, 12345; "Trailing zeros beyond scale")]
+        #[test_case("12345678901234567890.12345678", 38, 8, 1234567890123456789012345678; "Above rust_decimal precision")]
+        fn parse_valid(value: &str, precision: u8, scale: i8, expected: i128) {
+            assert_eq!(parse_mysql_decimal::<Decimal128Type>(value.as_bytes(), precision, scale).unwrap(), expected);
+        }
+
+        #[test_case("1.235", 19, 2; "Would round")]
+        #[test_case("123456", 5, 0; "Exceeds precision")]
+        #[test_case("abc", 19, 2; "Not a number")]
+        fn parse_invalid(value: &str, precision: u8, scale: i8) {
+            assert!(parse_mysql_decimal::<Decimal128Type>(value.as_bytes(), precision, scale).is_err());
+        }
+
+        #[test]
+        fn decimal256_column() {
+            let data = Arc::new(ColumnData::new(&decimal_column(65, 30, false), &ColumnOptions::default()).unwrap());
+            let mut column = Column::from_data(data).unwrap();
+            Column::push(&mut column, Value::Bytes(b"12345678901234567890123456789012345.123456789012345678901234567890".to_vec())).unwrap();
+            let array = column.finish().unwrap();
+            assert_eq!(array.data_type(), &DataType::Decimal256(65, 30));
+            assert_eq!(array.len(), 1);
         }
     }
 }
@@ -0,0 +1,538 @@
+use crate::data::columns::{EXTENSION_NAME_KEY, JSON_EXTENSION_NAME};
+use anyhow::{Context, Result, bail};
+use arrow::array::{
+    ArrayRef, BinaryArray, BooleanArray, Date32Array, Decimal128Array, Decimal256Array, FixedSizeBinaryArray, Float32Array,
+    Float64Array, Int16Array, Int32Array, Int64Array, IntervalMonthDayNanoArray, ListArray, StringArray,
+    Time64MicrosecondArray, TimestampMicrosecondArray, UInt32Array,
+};
+use arrow::buffer::{NullBuffer, OffsetBuffer};
+use arrow::compute::cast;
+use arrow::compute::kernels::cast_utils::parse_decimal;
+use arrow::datatypes::{
+    DECIMAL128_MAX_PRECISION, DECIMAL256_MAX_PRECISION, DataType, Decimal128Type, Decimal256Type, DecimalType, Field,
+    IntervalMonthDayNano, IntervalUnit, TimeUnit,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio_postgres::types::{FromSql, Kind, Type};
+
+const UUID_EXTENSION_NAME: &str = "arrow.uuid";
+//Field metadata key holding the declared type of the source column, such as numeric(12,2) or text[]
+const TYPE_KEY: &str = "postgres.type";
+//Field metadata key holding the labels of enum columns as a JSON array
+const ALLOWED_VALUES_KEY: &str = "postgres.allowed_values";
+//Postgres dates and timestamps count from 2000-01-01 rather than the Unix epoch
+const EPOCH_DAYS: i32 = 10_957;
+const EPOCH_MICROSECONDS: i64 = 946_684_800_000_000;
+//Sign of the binary numeric format marking NaN and, since postgres 14, infinite values
+const NUMERIC_NEGATIVE: u16 = 0x4000;
+const NUMERIC_SPECIAL: u16 = 0xC000;
+
+///Raw binary value of a column, decoded by PostgresType rather than by the postgres crate so any type can be read.
+pub struct RawValue<'a>(pub &'a [u8]);
+
+impl<'a> FromSql<'a> for RawValue<'a> {
+    fn from_sql(_ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        Ok(RawValue(raw))
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+}
+
+///How a postgres type is represented in Arrow.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PostgresType {
+    Boolean,
+    Int16,
+    Int32,
+    Int64,
+    Oid,
+    Float32,
+    Float64,
+    ///numeric with a declared precision of up to 76 digits. Negative scales are widened to a scale of 0.
+    Decimal { precision: u8, scale: i8 },
+    ///numeric without a declared precision (or with one too large for Arrow), written as its exact text.
+    Numeric,
+    Text,
+    Enum,
+    Json,
+    Jsonb,
+    Bytea,
+    Uuid,
+    Date,
+    Time,
+    Timestamp,
+    TimestampTz,
+    Interval,
+    ///One dimensional array of the element type.
+    Array(Box<PostgresType>),
+}
+
+impl PostgresType {
+    ///Gets the representation of a column of the given type. type_modifier is the column's atttypmod, holding the precision
+    ///and scale of numeric columns.
+    pub fn new(ty: &Type, type_modifier: i32) -> Result<PostgresType> {
+        match ty.kind() {
+            //Array columns carry the modifier of their elements
+            Kind::Array(element) => return Ok(PostgresType::Array(Box::new(PostgresType::new(element, type_modifier)?))),
+            //Domains are sent in the binary format of the type they are based on
+            Kind::Domain(base) => return PostgresType::new(base, type_modifier),
+            Kind::Enum(_) => return Ok(PostgresType::Enum),
+            _ => {}
+        }
+        let postgres_type = match *ty {
+            Type::BOOL => PostgresType::Boolean,
+            Type::INT2 => PostgresType::Int16,
+            Type::INT4 => PostgresType::Int32,
+            Type::INT8 => PostgresType::Int64,
+            Type::OID => PostgresType::Oid,
+            Type::FLOAT4 => PostgresType::Float32,
+            Type::FLOAT8 => PostgresType::Float64,
+            Type::NUMERIC => PostgresType::get_numeric(type_modifier),
+            Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME | Type::CHAR => PostgresType::Text,
+            Type::JSON => PostgresType::Json,
+            Type::JSONB => PostgresType::Jsonb,
+            Type::BYTEA => PostgresType::Bytea,
+            Type::UUID => PostgresType::Uuid,
+            Type::DATE => PostgresType::Date,
+            Type::TIME => PostgresType::Time,
+            Type::TIMESTAMP => PostgresType::Timestamp,
+            Type::TIMESTAMPTZ => PostgresType::TimestampTz,
+            Type::INTERVAL => PostgresType::Interval,
+            //Extension types without a fixed oid, sent as text
+            _ if ty.name() == "citext" => PostgresType::Text,
+            _ => bail!("Unsupported postgres type {}.", ty.name()),
+        };
+        Ok(postgres_type)
+    }
+
+    fn get_numeric(type_modifier: i32) -> PostgresType {
+        //Unconstrained numeric columns have a modifier of -1
+        if type_modifier < 4 {
+            return PostgresType::Numeric;
+        }
+        let mut precision = ((type_modifier - 4) >> 16) & 0xFFFF;
+        //The scale is an 11 bit signed integer. Negative scales round to the left of the decimal point, so the values are
+        //integers with up to precision - scale digits
+        let mut scale = (((type_modifier - 4) & 0x7FF) ^ 0x400) - 0x400;
+        if scale < 0 {
+            precision -= scale;
+            scale = 0;
+        }
+        if precision > DECIMAL256_MAX_PRECISION as i32 || scale > precision {
+            return PostgresType::Numeric;
+        }
+        PostgresType::Decimal {
+            precision: precision as u8,
+            scale: scale as i8,
+        }
+    }
+
+    pub fn data_type(&self) -> DataType {
+        match self {
+            PostgresType::Boolean => DataType::Boolean,
+            PostgresType::Int16 => DataType::Int16,
+            PostgresType::Int32 => DataType::Int32,
+            PostgresType::Int64 => DataType::Int64,
+            PostgresType::Oid => DataType::UInt32,
+            PostgresType::Float32 => DataType::Float32,
+            PostgresType::Float64 => DataType::Float64,
+            PostgresType::Decimal { precision, scale } if *precision <= DECIMAL128_MAX_PRECISION => {
+                DataType::Decimal128(*precision, *scale)
+            }
+            PostgresType::Decimal { precision, scale } => DataType::Decimal256(*precision, *scale),
+            PostgresType::Numeric | PostgresType::Text | PostgresType::Json | PostgresType::Jsonb => DataType::Utf8,
+            PostgresType::Enum => DataType::Dictionary(Box::new(DataType::Int16), Box::new(DataType::Utf8)),
+            PostgresType::Bytea => DataType::Binary,
+            PostgresType::Uuid => DataType::FixedSizeBinary(16),
+            PostgresType::Date => DataType::Date32,
+            PostgresType::Time => DataType::Time64(TimeUnit::Microsecond),
+            PostgresType::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, None),
+            PostgresType::TimestampTz => DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            PostgresType::Interval => DataType::Interval(IntervalUnit::MonthDayNano),
+            PostgresType::Array(element) => DataType::List(Arc::new(Field::new_list_field(element.data_type(), true))),
+        }
+    }
+
+    ///Gets the schema field of a column. declared_type is the type as written in the table definition, when it is known.
+    pub fn get_schema_field(&self, name: &str, ty: &Type, declared_type: Option<&str>, nullable: bool) -> Field {
+        let mut metadata = HashMap::new();
+        match self {
+            PostgresType::Json | PostgresType::Jsonb => {
+                metadata.insert(EXTENSION_NAME_KEY.to_string(), JSON_EXTENSION_NAME.to_string());
+            }
+            PostgresType::Uuid => {
+                metadata.insert(EXTENSION_NAME_KEY.to_string(), UUID_EXTENSION_NAME.to_string());
+            }
+            _ => {}
+        }
+        if let Kind::Enum(labels) = ty.kind() {
+            metadata.insert(ALLOWED_VALUES_KEY.to_string(), serde_json::to_string(labels).unwrap_or_default());
+        }
+        metadata.insert(TYPE_KEY.to_string(), declared_type.unwrap_or(ty.name()).to_string());
+        Field::new(name, self.data_type(), nullable).with_metadata(metadata)
+    }
+
+    ///Builds an array from the binary values of a column, None being null.
+    pub fn build_array(&self, values: &[Option<&[u8]>]) -> Result<ArrayRef> {
+        let array: ArrayRef = match self {
+            PostgresType::Boolean => Arc::new(
+                values
+                    .iter()
+                    .map(|value| value.map(|bytes| read::<1>(bytes).map(|b| b[0] != 0)).transpose())
+                    .collect::<Result<BooleanArray>>()?,
+            ),
+            PostgresType::Int16 => Arc::new(decode(values, |bytes| Ok(i16::from_be_bytes(read(bytes)?)))?.collect::<Int16Array>()),
+            PostgresType::Int32 => Arc::new(decode(values, |bytes| Ok(i32::from_be_bytes(read(bytes)?)))?.collect::<Int32Array>()),
+            PostgresType::Int64 => Arc::new(decode(values, |bytes| Ok(i64::from_be_bytes(read(bytes)?)))?.collect::<Int64Array>()),
+            PostgresType::Oid => Arc::new(decode(values, |bytes| Ok(u32::from_be_bytes(read(bytes)?)))?.collect::<UInt32Array>()),
+            PostgresType::Float32 => {
+                Arc::new(decode(values, |bytes| Ok(f32::from_be_bytes(read(bytes)?)))?.collect::<Float32Array>())
+            }
+            PostgresType::Float64 => {
+                Arc::new(decode(values, |bytes| Ok(f64::from_be_bytes(read(bytes)?)))?.collect::<Float64Array>())
+            }
+            PostgresType::Decimal { precision, scale } if *precision <= DECIMAL128_MAX_PRECISION => Arc::new(
+                decode(values, |bytes| parse_numeric::<Decimal128Type>(bytes, *precision, *scale))?
+                    .collect::<Decimal128Array>()
+                    .with_precision_and_scale(*precision, *scale)?,
+            ),
+            PostgresType::Decimal { precision, scale } => Arc::new(
+                decode(values, |bytes| parse_numeric::<Decimal256Type>(bytes, *precision, *scale))?
+                    .collect::<Decimal256Array>()
+                    .with_precision_and_scale(*precision, *scale)?,
+            ),
+            PostgresType::Numeric => Arc::new(decode(values, format_numeric)?.collect::<StringArray>()),
+            PostgresType::Text | PostgresType::Json => Arc::new(decode(values, read_text)?.collect::<StringArray>()),
+            PostgresType::Enum => cast(
+                &decode(values, read_text)?.collect::<StringArray>(),
+                &self.data_type(),
+            )?,
+            PostgresType::Jsonb => Arc::new(
+                decode(values, |bytes| match bytes.split_first() {
+                    Some((1, json)) => read_text(json),
+                    _ => bail!("Unsupported jsonb format version."),
+                })?
+                .collect::<StringArray>(),
+            ),
+            PostgresType::Bytea => Arc::new(values.iter().copied().collect::<BinaryArray>()),
+            PostgresType::Uuid => {
+                Arc::new(FixedSizeBinaryArray::try_from_sparse_iter_with_size(values.iter().copied(), 16)?)
+            }
+            PostgresType::Date => Arc::new(
+                decode(values, |bytes| match i32::from_be_bytes(read(bytes)?) {
+                    i32::MAX | i32::MIN => bail!("Infinite dates cannot be backed up."),
+                    days => Ok(days + EPOCH_DAYS),
+                })?
+                .collect::<Date32Array>(),
+            ),
+            PostgresType::Time => {
+                Arc::new(decode(values, |bytes| Ok(i64::from_be_bytes(read(bytes)?)))?.collect::<Time64MicrosecondArray>())
+            }
+            PostgresType::Timestamp | PostgresType::TimestampTz => {
+                let array = decode(values, |bytes| match i64::from_be_bytes(read(bytes)?) {
+                    i64::MAX | i64::MIN => bail!("Infinite timestamps cannot be backed up."),
+                    microseconds => microseconds
+                        .checked_add(EPOCH_MICROSECONDS)
+                        .context("Timestamp is out of the range of microsecond timestamps since the Unix epoch."),
+                })?
+                .collect::<TimestampMicrosecondArray>();
+                match self {
+                    PostgresType::TimestampTz => Arc::new(array.with_timezone("UTC")),
+                    _ => Arc::new(array),
+                }
+            }
+            PostgresType::Interval => Arc::new(
+                decode(values, |bytes| {
+                    let bytes = read::<16>(bytes)?;
+                    let microseconds = i64::from_be_bytes(bytes[0..8].try_into()?);
+                    let days = i32::from_be_bytes(bytes[8..12].try_into()?);
+                    let months = i32::from_be_bytes(bytes[12..16].try_into()?);
+                    let nanoseconds = microseconds
+                        .checked_mul(1000)
+                        .with_context(|| format!("Interval of {microseconds} microseconds does not fit in nanoseconds."))?;
+                    Ok(IntervalMonthDayNano::new(months, days, nanoseconds))
+                })?
+                .collect::<IntervalMonthDayNanoArray>(),
+            ),
+            PostgresType::Array(element) => {
+                let mut elements = Vec::new();
+                let mut offsets = vec![0];
+                for value in values.iter() {
+                    if let Some(bytes) = value {
+                        read_array(bytes, &mut elements)?;
+                    }
+                    offsets.push(elements.len() as i32);
+                }
+                let field = Arc::new(Field::new_list_field(element.data_type(), true));
+                let nulls = NullBuffer::from_iter(values.iter().map(|value| value.is_some()));
+                Arc::new(ListArray::try_new(
+                    field,
+                    OffsetBuffer::new(offsets.into()),
+                    element.build_array(&elements)?,
+                    Some(nulls),
+                )?)
+            }
+        };
+        Ok(array)
+    }
+}
+
+//Decodes every non-null value, failing on the first value that cannot be decoded
+fn decode<'a, T>(
+    values: &'a [Option<&'a [u8]>],
+    decoder: impl Fn(&'a [u8]) -> Result<T>,
+) -> Result<impl Iterator<Item = Option<T>>> {
+    let decoded = values.iter().map(|value| value.map(&decoder).transpose()).collect::<Result<Vec<_>>>()?;
+    Ok(decoded.into_iter())
+}
+
+fn read<const N: usize>(bytes: &[u8]) -> Result<[u8; N]> {
+    bytes.try_into().with_context(|| format!("Expected a {N} byte value, got {} bytes.", bytes.len()))
+}
+
+fn read_text(bytes: &[u8]) -> Result<&str> {
+    str::from_utf8(bytes).context("Postgres text value is not valid UTF-8.")
+}
+
+//Splits a one dimensional array in the binary format into its elements, appending them to elements
+fn read_array<'a>(bytes: &'a [u8], elements: &mut Vec<Option<&'a [u8]>>) -> Result<()> {
+    let mut reader = BinaryReader { bytes };
+    let dimensions = reader.read_i32()?;
+    //Whether the array contains nulls, followed by the oid of its elements
+    reader.take(8)?;
+    match dimensions {
+        0 => return Ok(()),
+        1 => {}
+        _ => bail!("Arrays with {dimensions} dimensions are not supported, only one dimensional arrays can be backed up."),
+    }
+    let length = reader.read_i32()?;
+    //Lower bound of the dimension
+    reader.take(4)?;
+    for _ in 0..length {
+        let element_length = reader.read_i32()?;
+        if element_length < 0 {
+            elements.push(None);
+        } else {
+            elements.push(Some(reader.take(element_length as usize)?));
+        }
+    }
+    Ok(())
+}
+
+struct BinaryReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> BinaryReader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < length {
+            bail!("Postgres value ended unexpectedly.");
+        }
+        let (value, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(value)
+    }
+
+    fn read_i16(&mut self) -> Result<i16> {
+        Ok(i16::from_be_bytes(read(self.take(2)?)?))
+    }
+
+    fn read_i32(&mut self) -> Result<i32> {
+        Ok(i32::from_be_bytes(read(self.take(4)?)?))
+    }
+}
+
+///Formats a numeric in the binary format as exact decimal text, such as -12.50, NaN or Infinity.
+fn format_numeric(bytes: &[u8]) -> Result<String> {
+    let mut reader = BinaryReader { bytes };
+    let digit_count = usize::try_from(reader.read_i16()?).context("Numeric value has a negative digit count.")?;
+    //Power of 10000 of the first digit
+    let weight = reader.read_i16()? as i32;
+    let sign = reader.read_i16()? as u16;
+    let display_scale = usize::try_from(reader.read_i16()?).context("Numeric value has a negative display scale.")?;
+    let mut digits = Vec::with_capacity(digit_count);
+    for _ in 0..digit_count {
+        digits.push(reader.read_i16()?);
+    }
+    match sign {
+        NUMERIC_SPECIAL => return Ok("NaN".to_string()),
+        0xD000 => return Ok("Infinity".to_string()),
+        0xF000 => return Ok("-Infinity".to_string()),
+        _ => {}
+    }
+    //Digits are base 10000, the digit at index i is multiplied by 10000^(weight - i)
+    let digit = |power: i32| -> i16 {
+        let index = weight - power;
+        if index >= 0 && (index as usize) < digits.len() { digits[index as usize] } else { 0 }
+    };
+    let mut text = String::new();
+    if sign == NUMERIC_NEGATIVE {
+        text.push('-');
+    }
+    if weight < 0 {
+        text.push('0');
+    } else {
+        text.push_str(&digit(weight).to_string());
+        for power in (0..weight).rev() {
+            text.push_str(&format!("{:04}", digit(power)));
+        }
+    }
+    if display_scale > 0 {
+        let mut fraction = String::new();
+        let mut power = -1;
+        while fraction.len() < display_scale {
+            fraction.push_str(&format!("{:04}", digit(power)));
+            power -= 1;
+        }
+        fraction.truncate(display_scale);
+        text.push('.');
+        text.push_str(&fraction);
+    }
+    Ok(text)
+}
+
+fn parse_numeric<T: DecimalType>(bytes: &[u8], precision: u8, scale: i8) -> Result<T::Native> {
+    let text = format_numeric(bytes)?;
+    if matches!(text.as_str(), "NaN" | "Infinity" | "-Infinity") {
+        bail!("Numeric value {text} cannot be backed up as a decimal.");
+    }
+    parse_decimal::<T>(&text, precision, scale)
+        .with_context(|| format!("Numeric value {text} does not fit in Decimal({precision}, {scale})."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::Int16Type;
+
+    //Encodes a numeric in the binary format from its base 10000 digits
+    fn numeric(weight: i16, sign: u16, display_scale: i16, digits: &[i16]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend((digits.len() as i16).to_be_bytes());
+        bytes.extend(weight.to_be_bytes());
+        bytes.extend(sign.to_be_bytes());
+        bytes.extend(display_scale.to_be_bytes());
+        for digit in digits {
+            bytes.extend(digit.to_be_bytes());
+        }
+        bytes
+    }
+
+    fn array(elements: &[Option<&[u8]>]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(1i32.to_be_bytes());
+        bytes.extend(0i32.to_be_bytes());
+        bytes.extend(Type::INT4.oid().to_be_bytes());
+        bytes.extend((elements.len() as i32).to_be_bytes());
+        bytes.extend(1i32.to_be_bytes());
+        for element in elements {
+            match element {
+                Some(element) => {
+                    bytes.extend((element.len() as i32).to_be_bytes());
+                    bytes.extend(*element);
+                }
+                None => bytes.extend((-1i32).to_be_bytes()),
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn numeric_type_modifiers() {
+        //numeric(12, 2), numeric(50, 0), numeric(3, -2) and numeric
+        assert_eq!(PostgresType::new(&Type::NUMERIC, (12 << 16) + 2 + 4).unwrap(), PostgresType::Decimal { precision: 12, scale: 2 });
+        assert_eq!(PostgresType::new(&Type::NUMERIC, (50 << 16) + 4).unwrap().data_type(), DataType::Decimal256(50, 0));
+        assert_eq!(PostgresType::new(&Type::NUMERIC, (3 << 16) + 0x7FE + 4).unwrap(), PostgresType::Decimal { precision: 5, scale: 0 });
+        assert_eq!(PostgresType::new(&Type::NUMERIC, -1).unwrap(), PostgresType::Numeric);
+        assert_eq!(PostgresType::new(&Type::NUMERIC, (100 << 16) + 4).unwrap(), PostgresType::Numeric);
+    }
+
+    #[test]
+    fn formats_numerics() {
+        assert_eq!(format_numeric(&numeric(1, 0, 2, &[12, 3456, 7800])).unwrap(), "123456.78");
+        assert_eq!(format_numeric(&numeric(-1, NUMERIC_NEGATIVE, 5, &[50])).unwrap(), "-0.00500");
+        assert_eq!(format_numeric(&numeric(2, 0, 0, &[1])).unwrap(), "100000000");
+        assert_eq!(format_numeric(&numeric(0, 0, 0, &[])).unwrap(), "0");
+        assert_eq!(format_numeric(&numeric(0, NUMERIC_SPECIAL, 0, &[])).unwrap(), "NaN");
+        assert!(format_numeric(&[0, 1]).is_err());
+        assert!(format_numeric(&numeric(0, 0, -1, &[])).is_err());
+        assert!(format_numeric(&[0xFF, 0xFF, 0, 0, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn builds_decimals() {
+        let decimal = PostgresType::Decimal { precision: 12, scale: 2 };
+        let values = [numeric(1, 0, 2, &[12, 3456, 7800]), numeric(0, NUMERIC_NEGATIVE, 1, &[5, 5000])];
+        let array = decimal.build_array(&[Some(&values[0]), None, Some(&values[1])]).unwrap();
+        let array = array.as_primitive::<Decimal128Type>();
+        assert_eq!(array.value(0), 12345678);
+        assert!(array.is_null(1));
+        assert_eq!(array.value(2), -550);
+        let nan = numeric(0, NUMERIC_SPECIAL, 0, &[]);
+        assert!(decimal.build_array(&[Some(&nan)]).is_err());
+        //numeric(3, -2)
+        let rounded = PostgresType::new(&Type::NUMERIC, (3 << 16) + 0x7FE + 4).unwrap();
+        let value = numeric(1, 0, 0, &[1, 2300]);
+        assert_eq!(rounded.build_array(&[Some(&value)]).unwrap().as_primitive::<Decimal128Type>().value(0), 12300);
+    }
+
+    #[test]
+    fn builds_dates_and_timestamps() {
+        let date = 0i32.to_be_bytes();
+        let array = PostgresType::Date.build_array(&[Some(&date)]).unwrap();
+        assert_eq!(array.as_primitive::<arrow::datatypes::Date32Type>().value(0), EPOCH_DAYS);
+        let timestamp = 1_000_000i64.to_be_bytes();
+        let array = PostgresType::TimestampTz.build_array(&[Some(&timestamp)]).unwrap();
+        assert_eq!(array.data_type(), &PostgresType::TimestampTz.data_type());
+        let infinity = i64::MAX.to_be_bytes();
+        assert!(PostgresType::Timestamp.build_array(&[Some(&infinity)]).is_err());
+        let out_of_range = (i64::MAX - 1).to_be_bytes();
+        assert!(PostgresType::Timestamp.build_array(&[Some(&out_of_range)]).is_err());
+    }
+
+    #[test]
+    fn builds_intervals() {
+        //Binary intervals are microseconds, days then months
+        let interval = |microseconds: i64, days: i32, months: i32| {
+            [microseconds.to_be_bytes().as_slice(), &days.to_be_bytes(), &months.to_be_bytes()].concat()
+        };
+        let value = interval(1_500_000, 2, 3);
+        let array = PostgresType::Interval.build_array(&[Some(&value)]).unwrap();
+        let array = array.as_primitive::<arrow::datatypes::IntervalMonthDayNanoType>();
+        assert_eq!(array.value(0), IntervalMonthDayNano::new(3, 2, 1_500_000_000));
+        let overflowing = interval(i64::MAX - 1, 0, 0);
+        assert!(PostgresType::Interval.build_array(&[Some(&overflowing)]).is_err());
+    }
+
+    #[test]
+    fn builds_arrays() {
+        let list = PostgresType::Array(Box::new(PostgresType::Int32));
+        let one = 1i32.to_be_bytes();
+        let two = 2i32.to_be_bytes();
+        let values = [array(&[Some(&one), None, Some(&two)]), array(&[])];
+        let array = list.build_array(&[Some(&values[0]), None, Some(&values[1])]).unwrap();
+        let array = array.as_list::<i32>();
+        assert_eq!(array.len(), 3);
+        assert_eq!(array.value(0).as_primitive::<arrow::datatypes::Int32Type>().iter().collect::<Vec<_>>(), vec![Some(1), None, Some(2)]);
+        assert!(array.is_null(1));
+        assert_eq!(array.value(2).len(), 0);
+    }
+
+    #[test]
+    fn builds_enums() {
+        let array = PostgresType::Enum.build_array(&[Some(b"open"), Some(b"closed"), Some(b"open")]).unwrap();
+        let array = array.as_dictionary::<Int16Type>();
+        assert_eq!(array.values().len(), 2);
+        assert_eq!(array.keys().values().to_vec(), vec![0, 1, 0]);
+    }
+
+    #[test]
+    fn unsupported_types() {
+        assert!(PostgresType::new(&Type::POINT, -1).is_err());
+        assert!(PostgresType::new(&Type::INT4_RANGE, -1).is_err());
+    }
+}
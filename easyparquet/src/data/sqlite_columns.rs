@@ -0,0 +1,195 @@
+use anyhow::{Result, bail};
+use arrow::array::{ArrayRef, BinaryArray, Float64Array, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field};
+use rusqlite::types::{Value, ValueRef};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+//Field metadata key holding the declared type of the source column, when it has one
+const TYPE_KEY: &str = "sqlite.type";
+
+///Arrow representation of a sqlite column, named after the storage class of its values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteType {
+    Integer,
+    Real,
+    Text,
+    Blob,
+}
+
+impl SqliteType {
+    ///Gets the type of a column from the affinity of its declared type, following sqlite's rules for determining affinity.
+    ///Returns None for columns that can store values of any class (NUMERIC and BLOB affinity, or no declared type at all),
+    ///whose type has to be found by scanning their values. INTEGER, REAL and TEXT columns can still store values their
+    ///affinity cannot convert, so their type is only the one their values are widened from.
+    pub fn from_declared_type(declared_type: Option<&str>) -> Option<SqliteType> {
+        let declared_type = declared_type?.to_uppercase();
+        if declared_type.contains("INT") {
+            Some(SqliteType::Integer)
+        } else if ["CHAR", "CLOB", "TEXT"].iter().any(|name| declared_type.contains(name)) {
+            Some(SqliteType::Text)
+        } else if declared_type.contains("BLOB") || declared_type.is_empty() {
+            None
+        } else if ["REAL", "FLOA", "DOUB"].iter().any(|name| declared_type.contains(name)) {
+            Some(SqliteType::Real)
+        } else {
+            None
+        }
+    }
+
+    ///Widens a scanned type so it can also hold value. Integers and reals widen to reals, numbers mixed with text are
+    ///written as text, and any class mixed with blobs is written as binary, see build_array.
+    pub fn merge(current: Option<SqliteType>, value: ValueRef) -> Option<SqliteType> {
+        let value_type = match value {
+            ValueRef::Null => return current,
+            ValueRef::Integer(_) => SqliteType::Integer,
+            ValueRef::Real(_) => SqliteType::Real,
+            ValueRef::Text(_) => SqliteType::Text,
+            ValueRef::Blob(_) => SqliteType::Blob,
+        };
+        let merged = match (current, value_type) {
+            (None, value_type) => value_type,
+            (Some(current), value_type) if current == value_type => current,
+            (Some(SqliteType::Blob), _) | (Some(_), SqliteType::Blob) => SqliteType::Blob,
+            (Some(SqliteType::Text), _) | (Some(_), SqliteType::Text) => SqliteType::Text,
+            _ => SqliteType::Real,
+        };
+        Some(merged)
+    }
+
+    pub fn data_type(&self) -> DataType {
+        match self {
+            SqliteType::Integer => DataType::Int64,
+            SqliteType::Real => DataType::Float64,
+            SqliteType::Text => DataType::Utf8,
+            SqliteType::Blob => DataType::Binary,
+        }
+    }
+
+    pub fn get_schema_field(&self, name: &str, declared_type: Option<&str>, nullable: bool) -> Field {
+        let mut metadata = HashMap::new();
+        if let Some(declared_type) = declared_type {
+            metadata.insert(TYPE_KEY.to_string(), declared_type.to_string());
+        }
+        Field::new(name, self.data_type(), nullable).with_metadata(metadata)
+    }
+
+    ///Builds an array from the values of a column. Values are converted to the column's type where that is lossless,
+    ///otherwise the column's type was wrong for its data and building fails. Numbers and text in a blob column are written
+    ///as the UTF-8 bytes of the text they would have in a text column.
+    pub fn build_array(&self, values: &[Value]) -> Result<ArrayRef> {
+        let array: ArrayRef = match self {
+            SqliteType::Integer => Arc::new(
+                values
+                    .iter()
+                    .map(|value| match value {
+                        Value::Null => Ok(None),
+                        Value::Integer(value) => Ok(Some(*value)),
+                        value => bail!("{} value in an integer column.", describe(value)),
+                    })
+                    .collect::<Result<Int64Array>>()?,
+            ),
+            SqliteType::Real => Arc::new(
+                values
+                    .iter()
+                    .map(|value| match value {
+                        Value::Null => Ok(None),
+                        Value::Integer(value) => Ok(Some(*value as f64)),
+                        Value::Real(value) => Ok(Some(*value)),
+                        value => bail!("{} value in a real column.", describe(value)),
+                    })
+                    .collect::<Result<Float64Array>>()?,
+            ),
+            SqliteType::Text => Arc::new(
+                values
+                    .iter()
+                    .map(|value| match value {
+                        Value::Null => Ok(None),
+                        Value::Integer(value) => Ok(Some(value.to_string())),
+                        Value::Real(value) => Ok(Some(value.to_string())),
+                        Value::Text(value) => Ok(Some(value.clone())),
+                        value => bail!("{} value in a text column.", describe(value)),
+                    })
+                    .collect::<Result<StringArray>>()?,
+            ),
+            SqliteType::Blob => Arc::new(
+                values
+                    .iter()
+                    .map(|value| match value {
+                        Value::Null => None,
+                        Value::Integer(value) => Some(value.to_string().into_bytes()),
+                        Value::Real(value) => Some(value.to_string().into_bytes()),
+                        Value::Text(value) => Some(value.clone().into_bytes()),
+                        Value::Blob(value) => Some(value.clone()),
+                    })
+                    .collect::<BinaryArray>(),
+            ),
+        };
+        Ok(array)
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "Null".to_string(),
+        Value::Integer(value) => format!("Integer {value}"),
+        Value::Real(value) => format!("Real {value}"),
+        Value::Text(value) => format!("Text '{value}'"),
+        Value::Blob(value) => format!("{} byte blob", value.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::Float64Type;
+    use test_case::test_case;
+
+    #[test_case(Some("INTEGER"), Some(SqliteType::Integer))]
+    #[test_case(Some("unsigned big int"), Some(SqliteType::Integer))]
+    #[test_case(Some("VARCHAR(255)"), Some(SqliteType::Text))]
+    #[test_case(Some("CLOB"), Some(SqliteType::Text))]
+    #[test_case(Some("DOUBLE PRECISION"), Some(SqliteType::Real))]
+    #[test_case(Some("FLOAT"), Some(SqliteType::Real))]
+    #[test_case(Some("BLOB"), None)]
+    #[test_case(Some("NUMERIC"), None)]
+    #[test_case(Some("DATETIME"), None)]
+    #[test_case(Some(""), None)]
+    #[test_case(None, None)]
+    fn affinity(declared_type: Option<&str>, expected: Option<SqliteType>) {
+        assert_eq!(SqliteType::from_declared_type(declared_type), expected);
+    }
+
+    #[test]
+    fn merges_scanned_values() {
+        let merge = |values: &[ValueRef]| values.iter().fold(None, |current, value| SqliteType::merge(current, *value));
+        assert_eq!(merge(&[ValueRef::Null]), None);
+        assert_eq!(merge(&[ValueRef::Integer(1), ValueRef::Null]), Some(SqliteType::Integer));
+        assert_eq!(merge(&[ValueRef::Integer(1), ValueRef::Real(1.5)]), Some(SqliteType::Real));
+        assert_eq!(merge(&[ValueRef::Real(1.5), ValueRef::Text(b"a")]), Some(SqliteType::Text));
+        assert_eq!(merge(&[ValueRef::Blob(b"a"), ValueRef::Blob(b"b")]), Some(SqliteType::Blob));
+        assert_eq!(merge(&[ValueRef::Integer(1), ValueRef::Blob(b"a")]), Some(SqliteType::Blob));
+        assert_eq!(merge(&[ValueRef::Text(b"a"), ValueRef::Blob(b"b")]), Some(SqliteType::Blob));
+    }
+
+    #[test]
+    fn builds_arrays() {
+        let values = vec![Value::Integer(2), Value::Null, Value::Real(0.5)];
+        let array = SqliteType::Real.build_array(&values).unwrap();
+        assert_eq!(array.as_primitive::<Float64Type>().value(0), 2.0);
+        assert!(array.is_null(1));
+        let array = SqliteType::Text.build_array(&values).unwrap();
+        assert_eq!(
+            array.as_string::<i32>().iter().collect::<Vec<_>>(),
+            vec![Some("2"), None, Some("0.5")]
+        );
+        assert!(SqliteType::Integer.build_array(&values).is_err());
+        let values = vec![Value::Text("a".to_string()), Value::Integer(2), Value::Blob(vec![3])];
+        let array = SqliteType::Blob.build_array(&values).unwrap();
+        assert_eq!(
+            array.as_binary::<i32>().iter().collect::<Vec<_>>(),
+            vec![Some(b"a".as_slice()), Some(b"2"), Some(&[3])]
+        );
+    }
+}
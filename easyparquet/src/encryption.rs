@@ -0,0 +1,213 @@
+use anyhow::{Context, Result, bail};
+use arrow::datatypes::Schema;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use parquet::arrow::ArrowSchemaConverter;
+use parquet::encryption::decrypt::{FileDecryptionProperties, KeyRetriever};
+use parquet::encryption::encrypt::FileEncryptionProperties;
+use parquet::errors::ParquetError;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+///Looks up the AES keys Parquet files are encrypted with by their id. Ids are stored unencrypted in the files so readers can find their keys.
+pub trait KeyProvider: Send + Sync {
+    fn get_key(&self, key_id: &str) -> Result<Vec<u8>>;
+}
+
+///Keys read from a local JSON file mapping key ids to base64 encoded 128 bit keys, such as {"footer": "<base64>"}.
+pub struct LocalKeyFile {
+    keys: HashMap<String, Vec<u8>>,
+}
+
+impl LocalKeyFile {
+    pub fn open(path: &Path) -> Result<LocalKeyFile> {
+        let contents = fs::read_to_string(path).with_context(|| format!("Failed to read key file {}", path.display()))?;
+        let encoded: HashMap<String, String> =
+            serde_json::from_str(&contents).with_context(|| format!("Invalid key file {}", path.display()))?;
+        let mut keys = HashMap::new();
+        for (key_id, key) in encoded {
+            let key = STANDARD.decode(key.trim()).with_context(|| format!("Key {key_id} is not valid base64"))?;
+            //The parquet crate only implements AES-128
+            if key.len() != 16 {
+                bail!("Key {key_id} must be 16 bytes long, got {} bytes.", key.len());
+            }
+            keys.insert(key_id, key);
+        }
+        Ok(LocalKeyFile { keys })
+    }
+}
+
+impl KeyProvider for LocalKeyFile {
+    fn get_key(&self, key_id: &str) -> Result<Vec<u8>> {
+        match self.keys.get(key_id) {
+            Some(key) => Ok(key.clone()),
+            None => bail!("No key with id {key_id}."),
+        }
+    }
+}
+
+///Parquet modular encryption of the files written by a ParquetWriter.
+///Columns with a key of their own are encrypted with it. When any column has its own key, the remaining columns are left
+///unencrypted, otherwise every column is encrypted with the footer key.
+#[derive(Clone)]
+pub struct EncryptionOptions {
+    pub key_provider: Arc<dyn KeyProvider>,
+    ///Key the file metadata (footer) is encrypted or, with plaintext_footer, signed with.
+    pub footer_key_id: String,
+    ///Ids of the keys columns are encrypted with, keyed by column name. Applies to every leaf of nested columns.
+    pub column_key_ids: HashMap<String, String>,
+    ///Leave the footer readable (but signed) so tools without the keys can still read the schema and unencrypted columns.
+    pub plaintext_footer: bool,
+}
+
+impl fmt::Debug for EncryptionOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptionOptions")
+            .field("footer_key_id", &self.footer_key_id)
+            .field("column_key_ids", &self.column_key_ids)
+            .field("plaintext_footer", &self.plaintext_footer)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EncryptionOptions {
+    pub fn new(key_provider: Arc<dyn KeyProvider>, footer_key_id: &str) -> EncryptionOptions {
+        EncryptionOptions {
+            key_provider,
+            footer_key_id: footer_key_id.to_string(),
+            column_key_ids: HashMap::new(),
+            plaintext_footer: false,
+        }
+    }
+
+    pub fn with_column_key(mut self, column: &str, key_id: &str) -> EncryptionOptions {
+        self.column_key_ids.insert(column.to_string(), key_id.to_string());
+        self
+    }
+
+    pub fn get_encryption_properties(&self, schema: &Schema) -> Result<FileEncryptionProperties> {
+        for column in self.column_key_ids.keys() {
+            if schema.field_with_name(column).is_err() {
+                bail!("Encryption key configured for unknown column {column}.");
+            }
+        }
+        let footer_key = self.key_provider.get_key(&self.footer_key_id)?;
+        let mut builder = FileEncryptionProperties::builder(footer_key)
+            .with_footer_key_metadata(self.footer_key_id.as_bytes().to_vec())
+            .with_plaintext_footer(self.plaintext_footer);
+        //Keys are set per leaf column, so nested Arrow columns are expanded to their leaves
+        let descriptor = ArrowSchemaConverter::new().convert(schema)?;
+        for column in descriptor.columns() {
+            if let Some(key_id) = self.column_key_ids.get(&column.path().parts()[0]) {
+                let key = self.key_provider.get_key(key_id)?;
+                builder = builder.with_column_key_and_metadata(&column.path().string(), key, key_id.as_bytes().to_vec());
+            }
+        }
+        Ok(builder.build()?)
+    }
+}
+
+///Retrieves the keys of an encrypted file from the ids stored in its key metadata.
+struct ProviderKeyRetriever {
+    key_provider: Arc<dyn KeyProvider>,
+}
+
+impl KeyRetriever for ProviderKeyRetriever {
+    fn retrieve_key(&self, key_metadata: &[u8]) -> parquet::errors::Result<Vec<u8>> {
+        let key_id = std::str::from_utf8(key_metadata)
+            .map_err(|e| ParquetError::General(format!("Key metadata is not a key id: {e}")))?;
+        self.key_provider
+            .get_key(key_id)
+            .map_err(|e| ParquetError::General(e.to_string()))
+    }
+}
+
+///Gets the properties for reading files written with EncryptionOptions, retrieving each key from key_provider as it is needed.
+pub fn get_decryption_properties(key_provider: Arc<dyn KeyProvider>) -> Result<FileDecryptionProperties> {
+    let retriever = Arc::new(ProviderKeyRetriever { key_provider });
+    Ok(FileDecryptionProperties::with_key_retriever(retriever).build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writers::test_util::{self, batch};
+    use crate::writers::{ParquetOptions, ParquetWriterFactory};
+    use arrow::array::RecordBatch;
+    use parquet::arrow::arrow_reader::{ArrowReaderOptions, ParquetRecordBatchReaderBuilder};
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    const FOOTER_KEY: &str = "MDEyMzQ1Njc4OWFiY2RlZg==";
+    const PII_KEY: &str = "ZmVkY2JhOTg3NjU0MzIxMA==";
+
+    fn key_file(directory: &Path) -> Arc<LocalKeyFile> {
+        let path = directory.join("keys.json");
+        fs::write(&path, format!(r#"{{"footer": "{FOOTER_KEY}", "pii": "{PII_KEY}"}}"#)).unwrap();
+        Arc::new(LocalKeyFile::open(&path).unwrap())
+    }
+
+    fn write(path: PathBuf, encryption: EncryptionOptions) -> Result<()> {
+        let options = ParquetOptions { encryption: Some(encryption), ..Default::default() };
+        test_util::write(&ParquetWriterFactory::new(path).with_options(options), batch(2).schema(), &[batch(2)])
+    }
+
+    fn read(path: &Path, decryption: Option<FileDecryptionProperties>) -> Result<RecordBatch> {
+        let mut options = ArrowReaderOptions::new();
+        if let Some(decryption) = decryption {
+            options = options.with_file_decryption_properties(decryption);
+        }
+        let mut reader = ParquetRecordBatchReaderBuilder::try_new_with_options(File::open(path)?, options)?.build()?;
+        Ok(reader.next().unwrap()?)
+    }
+
+    #[test]
+    fn round_trip() {
+        let directory = tempfile::tempdir().unwrap();
+        let keys = key_file(directory.path());
+        let path = directory.path().join("users.parquet");
+        write(path.clone(), EncryptionOptions::new(keys.clone(), "footer").with_column_key("name", "pii")).unwrap();
+
+        assert!(!fs::read(&path).unwrap().windows(6).any(|window| window == b"name 1"));
+        assert!(read(&path, None).is_err());
+        let decrypted = read(&path, Some(get_decryption_properties(keys).unwrap())).unwrap();
+        assert_eq!(decrypted.column(1).as_ref(), batch(2).column(1).as_ref());
+    }
+
+    #[test]
+    fn wrong_key() {
+        let directory = tempfile::tempdir().unwrap();
+        let keys = key_file(directory.path());
+        let path = directory.path().join("users.parquet");
+        write(path.clone(), EncryptionOptions::new(keys, "footer")).unwrap();
+
+        let other = directory.path().join("other.json");
+        fs::write(&other, format!(r#"{{"footer": "{PII_KEY}"}}"#)).unwrap();
+        let other = Arc::new(LocalKeyFile::open(&other).unwrap());
+        assert!(read(&path, Some(get_decryption_properties(other).unwrap())).is_err());
+    }
+
+    #[test]
+    fn invalid_configuration() {
+        let directory = tempfile::tempdir().unwrap();
+        let keys = key_file(directory.path());
+        let path = directory.path().join("users.parquet");
+        assert!(write(path.clone(), EncryptionOptions::new(keys.clone(), "missing")).is_err());
+        assert!(write(path.clone(), EncryptionOptions::new(keys.clone(), "footer").with_column_key("name", "missing")).is_err());
+        assert!(write(path, EncryptionOptions::new(keys, "footer").with_column_key("phone", "pii")).is_err());
+    }
+
+    #[test]
+    fn invalid_key_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("keys.json");
+        fs::write(&path, r#"{"short": "c2hvcnQ="}"#).unwrap();
+        assert!(LocalKeyFile::open(&path).is_err());
+        fs::write(&path, r#"{"footer": "not base64!"}"#).unwrap();
+        assert!(LocalKeyFile::open(&path).is_err());
+        assert!(LocalKeyFile::open(&directory.path().join("missing.json")).is_err());
+    }
+}
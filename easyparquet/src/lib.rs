@@ -4,9 +4,11 @@ mod config;
 pub use config::Config;
 
 use crate::backups::BatchBackup;
+use crate::readers::ColumnOptions;
 
 pub mod backups;
 mod data;
+pub mod encryption;
 pub mod readers;
 pub mod writers;
 
@@ -16,8 +18,25 @@ pub async fn run(config: Config) -> Result<(), Box<dyn Error>> {
     let root_directory = PathBuf::from(&config.backup_directory);
 
     let mut backup = BatchBackup::new(root_directory);
+    backup.set_column_options(ColumnOptions {
+        invalid_value_policy: config.invalid_value_policy,
+        json_inference_rows: config.json_inference_rows,
+        ..Default::default()
+    });
+    backup.set_output_format(config.output_format);
+    if let Some(url) = &config.object_store_url {
+        backup.set_object_store(url, &config.object_store_options)?;
+    }
     for table in config.database_tables.into_iter() {
-        backup.add_table(table);
+        let output_format = config.table_output_formats.get(&table).cloned();
+        let columns = config.table_columns.get(&table).cloned().unwrap_or_default();
+        let filter = config.table_filters.get(&table).cloned();
+        let column_policies = config.table_column_policies.get(&table).cloned().unwrap_or_default();
+        let table_options = backup.add_table(table);
+        table_options.output_format = output_format;
+        table_options.columns = columns;
+        table_options.filter = filter;
+        table_options.column_policies = column_policies;
     }
     backup.execute(pool).await?;
     Ok(())
@@ -1,7 +1,5 @@
-use dotenvy;
 use easyparquet::{Config, run};
 use std::process;
-use tokio;
 
 #[tokio::main]
 async fn main() {
@@ -1,11 +1,141 @@
-use crate::writers::DataWriterFactory;
-use anyhow::Result;
+use crate::writers::{DataWriter, DataWriterFactory};
+use anyhow::{Context, Result, bail};
+use flate2::read::MultiGzDecoder;
+use arrow::{array::RecordBatch, datatypes::Schema};
 use async_trait::async_trait;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
 #[async_trait]
 pub trait DataReader: Send + Sync {
     async fn read(&self, writer_factory: Box<dyn DataWriterFactory>) -> Result<()>;
 }
 
+pub(crate) enum WriteMessage {
+    Chunk(RecordBatch),
+    Finish,
+    Error,
+}
+
+///Runs a writer on a blocking thread, writing the chunks sent to it until it is told to finish or to abort after an error.
+pub(crate) fn spawn_writer(
+    writer_factory: Box<dyn DataWriterFactory>,
+    schema: Arc<Schema>,
+    buffer: usize,
+) -> (mpsc::Sender<WriteMessage>, JoinHandle<Result<()>>) {
+    let (tx, mut rx) = mpsc::channel(buffer);
+    let write_task = tokio::task::spawn_blocking(move || {
+        let mut writer = writer_factory.create();
+        let result = receive_messages(writer.as_mut(), schema, &mut rx);
+        //Whatever was written before the failure is discarded so that no partial backup is left behind
+        if let Err(e) = result {
+            if let Err(abort_error) = writer.abort() {
+                return Err(e.context(format!("Failed to abort the writer: {abort_error:#}")));
+            }
+            return Err(e);
+        }
+        Ok(())
+    });
+    (tx, write_task)
+}
+
+///Sets up the writer and hands it the messages received until it is finished or aborted.
+fn receive_messages(writer: &mut dyn DataWriter, schema: Arc<Schema>, rx: &mut mpsc::Receiver<WriteMessage>) -> Result<()> {
+    writer.setup(schema)?;
+    while let Some(message) = rx.blocking_recv() {
+        match message {
+            WriteMessage::Chunk(batch) => writer.write(&batch)?,
+            WriteMessage::Finish => return writer.finish(),
+            WriteMessage::Error => return writer.abort(),
+        }
+    }
+    //If this point is reached, sender channel closed too early, no Finish message was received, thus the end of the data stream was not reached and the database table cannot be properly backed up
+    bail!("End of data stream too early; improper backup");
+}
+
+///Opens a file to read, decompressing it if its extension is .gz.
+pub(crate) fn open_file(path: &Path) -> Result<Box<dyn Read + Send>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    if path.extension().is_some_and(|extension| extension == "gz") {
+        Ok(Box::new(MultiGzDecoder::new(BufReader::new(file))))
+    } else {
+        Ok(Box::new(file))
+    }
+}
+
+///Writes batches as they are read, aborting the writer when reading one fails. Blocks, so it must run on a blocking thread.
+pub(crate) fn write_batches(
+    writer_factory: Box<dyn DataWriterFactory>,
+    schema: Arc<Schema>,
+    batches: impl Iterator<Item = Result<RecordBatch>>,
+) -> Result<()> {
+    let (tx, write_task) = spawn_writer(writer_factory, schema, 2);
+    for batch in batches {
+        match batch {
+            Ok(batch) => tx.blocking_send(WriteMessage::Chunk(batch))?,
+            Err(e) => {
+                tx.blocking_send(WriteMessage::Error)?;
+                bail!(e);
+            }
+        }
+    }
+    tx.blocking_send(WriteMessage::Finish)?;
+
+    match Handle::current().block_on(write_task) {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => bail!(e),
+        Err(e) => bail!("Writer task failed: {e}."),
+    };
+    Ok(())
+}
+
+pub use crate::data::columns::{ColumnDefinition, ColumnOptions, InvalidValuePolicy};
+mod csv_reader;
+mod json_reader;
+mod memory_reader;
 mod mysql_reader;
-pub use mysql_reader::MysqlReader;
+mod postgres_reader;
+mod sqlite_reader;
+pub use csv_reader::CsvReader;
+pub use json_reader::JsonLinesReader;
+pub use memory_reader::MemoryReader;
+pub use mysql_reader::{ColumnSelection, MysqlReader};
+pub use postgres_reader::PostgresReader;
+pub use sqlite_reader::SqliteReader;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writers::test_util::batch;
+    use crate::writers::{ParquetOptions, ParquetWriterFactory};
+    use std::fs;
+
+    #[tokio::test]
+    async fn write_failure_aborts_rolling_writer() {
+        let directory = tempfile::tempdir().unwrap();
+        let options = ParquetOptions { max_rows_per_file: Some(10), ..Default::default() };
+        let factory = ParquetWriterFactory::new(directory.path().join("ledger.parquet")).with_options(options);
+        let (tx, write_task) = spawn_writer(Box::new(factory), batch(0).schema(), 2);
+        tx.send(WriteMessage::Chunk(batch(25))).await.unwrap();
+        //A batch without the id column does not match the schema the writer was set up with
+        tx.send(WriteMessage::Chunk(batch(5).project(&[1]).unwrap())).await.unwrap();
+        assert!(write_task.await.unwrap().is_err());
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn early_close_aborts_writer() {
+        let directory = tempfile::tempdir().unwrap();
+        let factory = ParquetWriterFactory::new(directory.path().join("users.parquet"));
+        let (tx, write_task) = spawn_writer(Box::new(factory), batch(0).schema(), 2);
+        tx.send(WriteMessage::Chunk(batch(5))).await.unwrap();
+        drop(tx);
+        assert!(write_task.await.unwrap().is_err());
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::readers::{DataReader, open_file, write_batches};
+use crate::writers::DataWriterFactory;
+use anyhow::{Result, bail};
+use arrow::csv::ReaderBuilder;
+use arrow::csv::reader::Format;
+use arrow::datatypes::Schema;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+//Rows sampled to infer the schema when none is given
+const DEFAULT_INFERENCE_ROWS: usize = 1000;
+
+///Reads a csv file, or a gzip compressed one ending in .gz, in record batches of chunk_size rows.
+///Unless a schema is given, column types are inferred from the leading rows. Empty fields are read as nulls.
+#[derive(Clone)]
+pub struct CsvReader {
+    path: PathBuf,
+    chunk_size: usize,
+    schema: Option<Arc<Schema>>,
+    inference_rows: usize,
+    header: bool,
+    delimiter: u8,
+}
+
+impl CsvReader {
+    pub fn new(path: PathBuf, chunk_size: usize) -> CsvReader {
+        CsvReader {
+            path,
+            chunk_size,
+            schema: None,
+            inference_rows: DEFAULT_INFERENCE_ROWS,
+            header: true,
+            delimiter: b',',
+        }
+    }
+
+    ///Reads the file with this schema instead of inferring one. Columns are matched by position.
+    pub fn with_schema(mut self, schema: Arc<Schema>) -> CsvReader {
+        self.schema = Some(schema);
+        self
+    }
+
+    ///Sets the number of rows the schema is inferred from, 0 inferring it from the whole file. Defaults to 1000.
+    ///Later values that do not fit the inferred types fail the backup.
+    pub fn with_inference_rows(mut self, inference_rows: usize) -> CsvReader {
+        self.inference_rows = inference_rows;
+        self
+    }
+
+    ///Whether the first line holds the column names. Without one, columns are named column_1, column_2 and so on.
+    pub fn with_header(mut self, header: bool) -> CsvReader {
+        self.header = header;
+        self
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> CsvReader {
+        self.delimiter = delimiter;
+        self
+    }
+
+    fn get_format(&self) -> Format {
+        Format::default().with_header(self.header).with_delimiter(self.delimiter)
+    }
+
+    fn read_blocking(&self, writer_factory: Box<dyn DataWriterFactory>) -> Result<()> {
+        if self.chunk_size == 0 {
+            bail!("Chunk size of csv files must be greater than 0.");
+        }
+        let schema = match &self.schema {
+            Some(schema) => schema.clone(),
+            None => {
+                let inference_rows = (self.inference_rows > 0).then_some(self.inference_rows);
+                let (schema, _) = self.get_format().infer_schema(open_file(&self.path)?, inference_rows)?;
+                Arc::new(schema)
+            }
+        };
+        let reader = ReaderBuilder::new(schema.clone())
+            .with_format(self.get_format())
+            .with_batch_size(self.chunk_size)
+            .build(open_file(&self.path)?)?;
+        write_batches(writer_factory, schema, reader.map(|batch| Ok(batch?)))
+    }
+}
+
+#[async_trait]
+impl DataReader for CsvReader {
+    async fn read(&self, writer_factory: Box<dyn DataWriterFactory>) -> Result<()> {
+        let reader = self.clone();
+        match tokio::task::spawn_blocking(move || reader.read_blocking(writer_factory)).await {
+            Ok(result) => result,
+            Err(e) => bail!("Reader task failed: {e}."),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backups::TableBackup;
+    use crate::writers::{MemoryWriterFactory, ParquetWriterFactory};
+    use arrow::array::{Array, AsArray, RecordBatch};
+    use arrow::compute::concat_batches;
+    use arrow::datatypes::{DataType, Field, Int64Type, TimeUnit};
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use std::path::Path;
+
+    const ACCOUNTS: &str = "user_id,currency_code,balance\n1,USD,10.5\n2,,20\n3,EUR,\n";
+
+    fn file(directory: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = directory.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    async fn read(reader: CsvReader) -> Result<Vec<RecordBatch>> {
+        let writer = MemoryWriterFactory::new();
+        reader.read(Box::new(writer.clone())).await?;
+        Ok(writer.batches().unwrap())
+    }
+
+    #[tokio::test]
+    async fn converts_rustbank_users() {
+        let directory = tempfile::tempdir().unwrap();
+        let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("../rustbank/data/Users.csv");
+        let path = directory.path().join("users.parquet");
+        let reader = Box::new(CsvReader::new(source, 300));
+        let writer = Box::new(ParquetWriterFactory::new(path.clone()));
+        TableBackup::new(path.clone()).execute(reader, writer).await.unwrap();
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        let schema = builder.schema().clone();
+        let names: Vec<&String> = schema.fields().iter().map(|field| field.name()).collect();
+        assert_eq!(
+            names,
+            vec!["first_name", "last_name", "email", "address", "phone_number", "date_of_birth", "created_at"]
+        );
+        assert_eq!(schema.field(5).data_type(), &DataType::Date32);
+        assert_eq!(schema.field(6).data_type(), &DataType::Timestamp(TimeUnit::Second, None));
+        let rows: usize = builder.build().unwrap().map(|batch| batch.unwrap().num_rows()).sum();
+        assert_eq!(rows, 1000);
+    }
+
+    #[tokio::test]
+    async fn infers_schema() {
+        let directory = tempfile::tempdir().unwrap();
+        let batches = read(CsvReader::new(file(directory.path(), "accounts.csv", ACCOUNTS), 2)).await.unwrap();
+        assert_eq!(batches.iter().map(|batch| batch.num_rows()).collect::<Vec<_>>(), vec![2, 1]);
+        let batch = concat_batches(&batches[0].schema(), &batches).unwrap();
+        assert_eq!(batch.schema().field(0).data_type(), &DataType::Int64);
+        assert_eq!(batch.schema().field(2).data_type(), &DataType::Float64);
+        assert!(batch.column(1).is_null(1));
+        assert!(batch.column(2).is_null(2));
+    }
+
+    #[tokio::test]
+    async fn reads_with_schema() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = file(directory.path(), "accounts.csv", &ACCOUNTS.replace(',', ";"));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("currency", DataType::Utf8, true),
+            Field::new("balance", DataType::Utf8, true),
+        ]));
+        let reader = CsvReader::new(path, 10).with_schema(schema.clone()).with_delimiter(b';');
+        let batches = read(reader).await.unwrap();
+        assert_eq!(batches[0].schema(), schema);
+        assert_eq!(batches[0].column(2).as_string::<i32>().value(0), "10.5");
+    }
+
+    #[tokio::test]
+    async fn reads_gzip_without_header() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("accounts.csv.gz");
+        let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        encoder.write_all(ACCOUNTS.split_once('\n').unwrap().1.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+        let batches = read(CsvReader::new(path, 10).with_header(false)).await.unwrap();
+        assert_eq!(batches[0].schema().field(0).name(), "column_1");
+        assert_eq!(batches[0].column(0).as_primitive::<Int64Type>().values().to_vec(), vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn invalid_rows_abort_writer() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = file(directory.path(), "accounts.csv", &format!("{ACCOUNTS}four,GBP,1\n"));
+        let writer = MemoryWriterFactory::new();
+        let reader = CsvReader::new(path.clone(), 2).with_inference_rows(3);
+        assert!(reader.read(Box::new(writer.clone())).await.is_err());
+        assert!(writer.batches().is_none());
+        //Inferring from the whole file finds the text value
+        let batches = read(CsvReader::new(path.clone(), 2).with_inference_rows(0)).await.unwrap();
+        assert_eq!(batches[0].schema().field(0).data_type(), &DataType::Utf8);
+
+        assert!(read(CsvReader::new(path, 0)).await.is_err());
+        assert!(read(CsvReader::new(directory.path().join("missing.csv"), 10)).await.is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::readers::{DataReader, open_file, write_batches};
+use crate::writers::DataWriterFactory;
+use anyhow::{Result, bail};
+use arrow::datatypes::Schema;
+use arrow::json::ReaderBuilder;
+use arrow::json::reader::infer_json_schema;
+use async_trait::async_trait;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+//Lines sampled to infer the schema when none is given
+const DEFAULT_INFERENCE_ROWS: usize = 1000;
+
+///Reads a file of newline-delimited JSON objects, or a gzip compressed one ending in .gz, in record batches of chunk_size rows.
+///Unless a schema is given, it is inferred from the leading lines and lines with fields not seen in them fail the backup.
+///With a given schema, fields missing from it are left out.
+#[derive(Clone)]
+pub struct JsonLinesReader {
+    path: PathBuf,
+    chunk_size: usize,
+    schema: Option<Arc<Schema>>,
+    inference_rows: usize,
+}
+
+impl JsonLinesReader {
+    pub fn new(path: PathBuf, chunk_size: usize) -> JsonLinesReader {
+        JsonLinesReader {
+            path,
+            chunk_size,
+            schema: None,
+            inference_rows: DEFAULT_INFERENCE_ROWS,
+        }
+    }
+
+    ///Reads the file with this schema instead of inferring one. Fields are matched by name.
+    pub fn with_schema(mut self, schema: Arc<Schema>) -> JsonLinesReader {
+        self.schema = Some(schema);
+        self
+    }
+
+    ///Sets the number of lines the schema is inferred from, 0 inferring it from the whole file. Defaults to 1000.
+    pub fn with_inference_rows(mut self, inference_rows: usize) -> JsonLinesReader {
+        self.inference_rows = inference_rows;
+        self
+    }
+
+    fn read_blocking(&self, writer_factory: Box<dyn DataWriterFactory>) -> Result<()> {
+        if self.chunk_size == 0 {
+            bail!("Chunk size of JSON lines files must be greater than 0.");
+        }
+        let (schema, strict) = match &self.schema {
+            Some(schema) => (schema.clone(), false),
+            None => {
+                let inference_rows = (self.inference_rows > 0).then_some(self.inference_rows);
+                let (schema, _) = infer_json_schema(BufReader::new(open_file(&self.path)?), inference_rows)?;
+                (Arc::new(schema), true)
+            }
+        };
+        let reader = ReaderBuilder::new(schema.clone())
+            .with_batch_size(self.chunk_size)
+            .with_strict_mode(strict)
+            .build(BufReader::new(open_file(&self.path)?))?;
+        write_batches(writer_factory, schema, reader.map(|batch| Ok(batch?)))
+    }
+}
+
+#[async_trait]
+impl DataReader for JsonLinesReader {
+    async fn read(&self, writer_factory: Box<dyn DataWriterFactory>) -> Result<()> {
+        let reader = self.clone();
+        match tokio::task::spawn_blocking(move || reader.read_blocking(writer_factory)).await {
+            Ok(result) => result,
+            Err(e) => bail!("Reader task failed: {e}."),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writers::{JsonLinesWriterFactory, MemoryWriterFactory};
+    use arrow::array::{Array, AsArray, RecordBatch};
+    use arrow::compute::concat_batches;
+    use arrow::datatypes::{DataType, Field, Int64Type};
+    use std::fs;
+    use std::path::Path;
+
+    const USERS: &str = "{\"id\": 1, \"name\": \"Jo\", \"tags\": [\"a\"]}\n{\"id\": 2, \"name\": null}\n{\"id\": 3, \"name\": \"Sam\", \"tags\": []}\n";
+
+    fn file(directory: &Path, contents: &str) -> PathBuf {
+        let path = directory.join("users.jsonl");
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    async fn read(reader: JsonLinesReader) -> Result<Vec<RecordBatch>> {
+        let writer = MemoryWriterFactory::new();
+        reader.read(Box::new(writer.clone())).await?;
+        Ok(writer.batches().unwrap())
+    }
+
+    #[tokio::test]
+    async fn infers_schema() {
+        let directory = tempfile::tempdir().unwrap();
+        let batches = read(JsonLinesReader::new(file(directory.path(), USERS), 2)).await.unwrap();
+        assert_eq!(batches.iter().map(|batch| batch.num_rows()).collect::<Vec<_>>(), vec![2, 1]);
+        let batch = concat_batches(&batches[0].schema(), &batches).unwrap();
+        assert_eq!(batch.schema().field(0).data_type(), &DataType::Int64);
+        assert!(matches!(batch.schema().field(2).data_type(), DataType::List(_)));
+        assert!(batch.column(1).is_null(1));
+        assert_eq!(batch.column(2).as_list::<i32>().value(0).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn reads_with_schema() {
+        let directory = tempfile::tempdir().unwrap();
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let reader = JsonLinesReader::new(file(directory.path(), USERS), 10).with_schema(schema.clone());
+        let batches = read(reader).await.unwrap();
+        assert_eq!(batches[0].schema(), schema);
+        assert_eq!(batches[0].column(0).as_primitive::<Int64Type>().values().to_vec(), vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn round_trips_written_files() {
+        let directory = tempfile::tempdir().unwrap();
+        let source = file(directory.path(), USERS);
+        let batches = read(JsonLinesReader::new(source.clone(), 10)).await.unwrap();
+        let batch = concat_batches(&batches[0].schema(), &batches).unwrap();
+        let path = directory.path().join("copy.jsonl");
+        let reader = JsonLinesReader::new(source, 10);
+        reader.read(Box::new(JsonLinesWriterFactory::new(path.clone()))).await.unwrap();
+        let copied = read(JsonLinesReader::new(path, 10).with_schema(batch.schema())).await.unwrap();
+        assert_eq!(copied, vec![batch]);
+    }
+
+    #[tokio::test]
+    async fn unseen_fields_abort_writer() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = file(directory.path(), &format!("{USERS}{{\"id\": 4, \"email\": \"a@example.com\"}}\n"));
+        let writer = MemoryWriterFactory::new();
+        let reader = JsonLinesReader::new(path.clone(), 10).with_inference_rows(3);
+        assert!(reader.read(Box::new(writer.clone())).await.is_err());
+        assert!(writer.batches().is_none());
+        assert_eq!(read(JsonLinesReader::new(path, 10)).await.unwrap()[0].num_columns(), 4);
+
+        let invalid = file(directory.path(), "{\"id\": 1}\nnot json\n");
+        assert!(read(JsonLinesReader::new(invalid, 10)).await.is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use crate::readers::{DataReader, WriteMessage, spawn_writer};
+use crate::writers::DataWriterFactory;
+use anyhow::{Result, bail};
+use arrow::{array::RecordBatch, datatypes::Schema};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+///Replays record batches held in memory, for backing up data that did not come from a database and for testing writers and
+///backups without one.
+pub struct MemoryReader {
+    schema: Arc<Schema>,
+    batches: Vec<RecordBatch>,
+    failure_after: Option<usize>,
+}
+
+impl MemoryReader {
+    pub fn new(schema: Arc<Schema>, batches: Vec<RecordBatch>) -> MemoryReader {
+        MemoryReader {
+            schema,
+            batches,
+            failure_after: None,
+        }
+    }
+
+    ///Makes the reader fail after replaying the given number of batches, as a database connection dropping partway through a
+    ///table would, so that the handling of failed backups can be tested.
+    pub fn with_failure_after(mut self, batches: usize) -> MemoryReader {
+        self.failure_after = Some(batches);
+        self
+    }
+}
+
+#[async_trait]
+impl DataReader for MemoryReader {
+    async fn read(&self, writer_factory: Box<dyn DataWriterFactory>) -> Result<()> {
+        let replayed = self.failure_after.unwrap_or(self.batches.len()).min(self.batches.len());
+        //Writers block, so they run on a blocking thread as they do for the other readers
+        let (tx, write_task) = spawn_writer(writer_factory, self.schema.clone(), 2);
+        for batch in &self.batches[..replayed] {
+            tx.send(WriteMessage::Chunk(batch.clone())).await?;
+        }
+        if self.failure_after.is_some() {
+            tx.send(WriteMessage::Error).await?;
+        } else {
+            tx.send(WriteMessage::Finish).await?;
+        }
+        match write_task.await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => bail!(e),
+            Err(e) => bail!("Writer task failed: {e}."),
+        }
+        if self.failure_after.is_some() {
+            bail!("Memory reader failed after {replayed} batches.");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writers::test_util;
+    use crate::writers::{MemoryWriterFactory, ParquetWriterFactory};
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Field};
+
+    fn batches() -> Vec<RecordBatch> {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        (0..3)
+            .map(|i| RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(vec![i, i + 10]))]).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn replays_batches() {
+        let writer = MemoryWriterFactory::new();
+        let reader = MemoryReader::new(batches()[0].schema(), batches());
+        reader.read(Box::new(writer.clone())).await.unwrap();
+        assert_eq!(writer.batches().unwrap(), batches());
+        assert_eq!(writer.schema().unwrap(), batches()[0].schema());
+    }
+
+    #[tokio::test]
+    async fn replays_empty_table() {
+        let writer = MemoryWriterFactory::new();
+        let reader = MemoryReader::new(batches()[0].schema(), Vec::new());
+        reader.read(Box::new(writer.clone())).await.unwrap();
+        assert!(writer.batches().unwrap().is_empty());
+        assert_eq!(writer.schema().unwrap(), batches()[0].schema());
+    }
+
+    #[tokio::test]
+    async fn failure_aborts_writer() {
+        let writer = MemoryWriterFactory::new();
+        let reader = MemoryReader::new(batches()[0].schema(), batches()).with_failure_after(2);
+        assert!(reader.read(Box::new(writer.clone())).await.is_err());
+        assert!(writer.batches().is_none());
+    }
+
+    #[tokio::test]
+    async fn write_failure_aborts_writer() {
+        let directory = tempfile::tempdir().unwrap();
+        let writer = ParquetWriterFactory::new(directory.path().join("users.parquet"));
+        //A batch of names does not match the schema the writer was set up with
+        let names = test_util::batch(2).project(&[1]).unwrap();
+        let reader = MemoryReader::new(batches()[0].schema(), vec![batches()[0].clone(), names]);
+        assert!(reader.read(Box::new(writer)).await.is_err());
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+}
@@ -1,98 +1,396 @@
-use crate::data::columns::{Column, ColumnData};
-use crate::readers::DataReader;
-use crate::writers::DataWriterFactory;
-use anyhow::{Result, bail};
+use crate::data::columns::{Column, ColumnData, ColumnDefinition, ColumnOptions, GEO_METADATA_KEY};
+use crate::readers::{DataReader, WriteMessage, spawn_writer};
+use crate::writers::{DataWriterFactory, append_extension, remove_file};
+use anyhow::{Context, Result, bail};
 use arrow::array::Array;
-use arrow::{array::RecordBatch, datatypes::Schema};
+use arrow::datatypes::Schema;
 use async_trait::async_trait;
 use futures::StreamExt;
+use mysql_async::consts::{ColumnFlags, ColumnType};
 use mysql_async::prelude::*;
-use mysql_async::{Pool, Row};
+use mysql_async::{Conn, Params, Pool, Row, Value};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+
+//Name, type, character set, collation, default, key, extra and comment of a column in information_schema.COLUMNS
+type DefinitionRow = (String, String, Option<String>, Option<String>, Option<String>, String, String, String);
+
+///Columns of a table that are backed up.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ColumnSelection {
+    #[default]
+    All,
+    ///Only these columns, in this order.
+    Include(Vec<String>),
+    ///Every column except these.
+    Exclude(Vec<String>),
+}
+
+impl ColumnSelection {
+    ///Selects columns from the columns of a table. Naming a column the table does not have is an error, so a misspelt
+    ///exclusion cannot let a column through, and so is selecting no columns at all.
+    pub fn select(&self, column_names: &[String]) -> Result<Vec<String>> {
+        let names = match self {
+            ColumnSelection::All => return Ok(column_names.to_vec()),
+            ColumnSelection::Include(names) | ColumnSelection::Exclude(names) => names,
+        };
+        if let Some(name) = names.iter().find(|name| !column_names.contains(name)) {
+            bail!("Column {name} does not exist.");
+        }
+        let selected: Vec<String> = match self {
+            ColumnSelection::Exclude(names) => column_names.iter().filter(|name| !names.contains(name)).cloned().collect(),
+            _ => names.clone(),
+        };
+        if selected.is_empty() {
+            bail!("No columns are selected.");
+        }
+        Ok(selected)
+    }
+}
 
 pub struct MysqlReader {
     pool: mysql_async::Pool,
-    table_name: String,
+    table_name: Option<String>,
+    query: String,
+    params: Params,
     chunk_size: usize,
+    column_options: ColumnOptions,
+    reject_path: Option<PathBuf>,
+    columns: ColumnSelection,
+    filter: Option<String>,
 }
 
 impl MysqlReader {
     pub fn new(pool: Pool, table_name: String, chunk_size: usize) -> MysqlReader {
+        let query = format!("SELECT * FROM {}", quote_identifier(&table_name));
+        MysqlReader {
+            table_name: Some(table_name),
+            ..MysqlReader::from_query(pool, query, Params::Empty, chunk_size)
+        }
+    }
+
+    ///Reads the result of a query rather than a table, such as a join or a SELECT with a WHERE clause.
+    ///The query is run as a prepared statement with params bound to its ? or :name placeholders.
+    ///Columns computed by the query have no information_schema definition, so ENUM, SET and GEOMETRY options for them
+    ///have to be configured in the column options.
+    pub fn from_query(pool: Pool, query: String, params: impl Into<Params>, chunk_size: usize) -> MysqlReader {
         MysqlReader {
-            pool: pool,
-            table_name: table_name,
-            chunk_size: chunk_size,
+            pool,
+            table_name: None,
+            query,
+            params: params.into(),
+            chunk_size,
+            column_options: ColumnOptions::default(),
+            reject_path: None,
+            columns: ColumnSelection::All,
+            filter: None,
         }
     }
+
+    pub fn with_column_options(mut self, column_options: ColumnOptions) -> MysqlReader {
+        self.column_options = column_options;
+        self
+    }
+
+    ///Sets the csv file rows rejected by InvalidValuePolicy::Reject are written to. The file is only created if a row is
+    ///rejected, and replaces the file of a previous run, which is removed when no row is rejected or the table fails to be read.
+    pub fn with_reject_path(mut self, reject_path: PathBuf) -> MysqlReader {
+        self.reject_path = Some(reject_path);
+        self
+    }
+
+    ///Sets the columns of the table that are read. Left out columns are not selected, so they never leave the database.
+    pub fn with_columns(mut self, columns: ColumnSelection) -> MysqlReader {
+        self.columns = columns;
+        self
+    }
+
+    ///Only reads the rows of the table matching a predicate, such as deleted_at IS NULL, used as the query's WHERE clause.
+    pub fn with_filter(mut self, filter: String) -> MysqlReader {
+        self.filter = Some(filter);
+        self
+    }
+
+    ///Gets the query that is run, applying the column selection and filter of a table.
+    async fn get_query(&self, conn: &mut Conn) -> Result<String> {
+        if self.columns == ColumnSelection::All && self.filter.is_none() {
+            return Ok(self.query.clone());
+        }
+        let Some(table_name) = &self.table_name else {
+            bail!("Columns and filters can only be set when reading a table, write them into the query instead.");
+        };
+        let select = match self.columns {
+            ColumnSelection::All => String::from("*"),
+            _ => {
+                let statement = conn.prep(&self.query).await?;
+                let column_names: Vec<String> =
+                    statement.columns().iter().map(|column| column.name_str().into_owned()).collect();
+                let selected = self
+                    .columns
+                    .select(&column_names)
+                    .with_context(|| format!("Invalid column selection for table {table_name}"))?;
+                selected.iter().map(|name| quote_identifier(name)).collect::<Vec<_>>().join(", ")
+            }
+        };
+        let mut query = format!("SELECT {select} FROM {}", quote_identifier(table_name));
+        if let Some(filter) = &self.filter {
+            query.push_str(&format!(" WHERE ({filter})"));
+        }
+        Ok(query)
+    }
 }
 
-enum WriteMessage {
-    Chunk(RecordBatch),
-    Finish,
-    Error,
+///Quotes a table or column name with backticks, so that names that are keywords or contain special characters can be used.
+fn quote_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+///Writes rows rejected by an InvalidValuePolicy to a csv file, creating it on the first rejected row. The file is staged
+///under a temporary name and only moved into place once the table has been read.
+struct RejectWriter {
+    path: Option<PathBuf>,
+    temp_path: Option<PathBuf>,
+    header: Vec<String>,
+    writer: Option<csv::Writer<File>>,
+}
+
+impl RejectWriter {
+    fn new(path: Option<PathBuf>) -> RejectWriter {
+        RejectWriter {
+            temp_path: path.as_deref().map(|path| append_extension(path, "temp")),
+            path,
+            header: Vec::new(),
+            writer: None,
+        }
+    }
+
+    ///Formats a value as csv text. Bytes that are not valid UTF-8 are written as 0x followed by their hex digits, as in
+    ///mysql's hexadecimal literals.
+    fn format_value(value: &Value) -> String {
+        match value {
+            Value::NULL => String::new(),
+            Value::Bytes(bytes) => match str::from_utf8(bytes) {
+                Ok(text) => text.to_string(),
+                Err(_) => format!("0x{}", bytes.iter().map(|byte| format!("{byte:02X}")).collect::<String>()),
+            },
+            Value::Int(v) => v.to_string(),
+            Value::UInt(v) => v.to_string(),
+            Value::Float(v) => v.to_string(),
+            Value::Double(v) => v.to_string(),
+            Value::Date(year, month, day, hours, minutes, seconds, micro_seconds) => {
+                format!("{year:04}-{month:02}-{day:02} {hours:02}:{minutes:02}:{seconds:02}.{micro_seconds:06}")
+            }
+            Value::Time(is_negative, days, hours, minutes, seconds, micro_seconds) => {
+                let sign = if *is_negative { "-" } else { "" };
+                let hours = *days * 24 + *hours as u32;
+                format!("{sign}{hours:02}:{minutes:02}:{seconds:02}.{micro_seconds:06}")
+            }
+        }
+    }
+
+    fn write(&mut self, values: &[Value]) -> Result<()> {
+        let writer = match self.writer {
+            Some(ref mut writer) => writer,
+            None => {
+                let Some(temp_path) = &self.temp_path else {
+                    bail!("A row was rejected but no reject file was set.");
+                };
+                let mut writer = csv::Writer::from_path(temp_path)?;
+                writer.write_record(&self.header)?;
+                self.writer.insert(writer)
+            }
+        };
+        writer.write_record(values.iter().map(RejectWriter::format_value))?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if let (Some(mut writer), Some(path), Some(temp_path)) = (self.writer.take(), &self.path, &self.temp_path) {
+            writer.flush()?;
+            drop(writer);
+            fs::rename(temp_path, path)?;
+        }
+        Ok(())
+    }
+
+    ///Removes the reject file, whether it is still staged or was already moved into place.
+    fn abort(&mut self) -> Result<()> {
+        self.writer = None;
+        for path in [&self.temp_path, &self.path].into_iter().flatten() {
+            remove_file(path)?;
+        }
+        Ok(())
+    }
 }
 
 impl MysqlReader {
-    fn get_columns(columns: &Vec<Arc<ColumnData>>) -> Vec<Column> {
+    ///Applies the configured session time zone, if any, and returns the time zone TIMESTAMP values will be sent in.
+    async fn get_time_zone(conn: &mut Conn, column_options: &ColumnOptions) -> Result<String> {
+        if let Some(time_zone) = &column_options.time_zone {
+            conn.exec_drop("SET time_zone = ?", (time_zone,)).await?;
+        }
+        let session_zone: String = conn
+            .query_first("SELECT @@session.time_zone")
+            .await?
+            .context("Could not read session time zone.")?;
+        //SYSTEM means the server's own time zone is in use, which is often only known by an ambiguous abbreviation such as CST,
+        //so the session is switched to UTC instead
+        if session_zone == "SYSTEM" {
+            conn.query_drop("SET time_zone = '+00:00'").await?;
+            Ok(String::from("UTC"))
+        } else {
+            Ok(session_zone)
+        }
+    }
+
+    ///Reads the information_schema definitions of the source columns without one configured.
+    ///The allowed values of ENUM and SET columns are taken from their column type.
+    async fn get_column_definitions(conn: &mut Conn, columns: &[mysql_async::Column], column_options: &mut ColumnOptions) -> Result<()> {
+        //Columns are looked up one source table at a time, columns computed by the query have no source table
+        let mut tables: HashMap<(String, String), HashMap<String, ColumnDefinition>> = HashMap::new();
+        for column in columns {
+            let name = column.name_str();
+            let table = (column.schema_str().into_owned(), column.org_table_str().into_owned());
+            if table.1.is_empty() || column_options.column_definitions.contains_key(name.as_ref()) {
+                continue;
+            }
+            if !tables.contains_key(&table) {
+                let rows: Vec<DefinitionRow> = conn
+                    .exec(
+                        "SELECT COLUMN_NAME, COLUMN_TYPE, CHARACTER_SET_NAME, COLLATION_NAME, COLUMN_DEFAULT, COLUMN_KEY, EXTRA, COLUMN_COMMENT \
+                         FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?",
+                        table.clone(),
+                    )
+                    .await
+                    .with_context(|| format!("Failed to read the column definitions of {}.{}", table.0, table.1))?;
+                let definitions = rows
+                    .into_iter()
+                    .map(|(column_name, column_type, character_set, collation, default, key, extra, comment)| {
+                        (column_name, ColumnDefinition { column_type, character_set, collation, default, key, extra, comment })
+                    })
+                    .collect();
+                tables.insert(table.clone(), definitions);
+            }
+            if let Some(definition) = tables[&table].get(column.org_name_str().as_ref()) {
+                column_options.column_definitions.insert(name.into_owned(), definition.clone());
+            }
+        }
+
+        for column in columns {
+            let name = column.name_str();
+            let flags = column.flags();
+            let is_enum = flags.contains(ColumnFlags::ENUM_FLAG) || column.column_type() == ColumnType::MYSQL_TYPE_ENUM;
+            let is_set = flags.contains(ColumnFlags::SET_FLAG) || column.column_type() == ColumnType::MYSQL_TYPE_SET;
+            if !(is_enum || is_set) || column_options.enum_values.contains_key(name.as_ref()) {
+                continue;
+            }
+            if let Some(definition) = column_options.column_definitions.get(name.as_ref()) {
+                let values = ColumnData::parse_allowed_values(&definition.column_type)?;
+                column_options.enum_values.insert(name.into_owned(), values);
+            }
+        }
+        Ok(())
+    }
+
+    ///Reads the SRID restriction of GEOMETRY columns that have none configured from information_schema.
+    async fn get_geometry_srids(conn: &mut Conn, columns: &[mysql_async::Column], column_options: &mut ColumnOptions) -> Result<()> {
+        for column in columns {
+            let name = column.name_str();
+            if column.column_type() != ColumnType::MYSQL_TYPE_GEOMETRY || column_options.geometry_srids.contains_key(name.as_ref()) {
+                continue;
+            }
+            let srid: Option<Option<u32>> = conn
+                .exec_first(
+                    "SELECT SRS_ID FROM information_schema.ST_GEOMETRY_COLUMNS WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? AND COLUMN_NAME = ?",
+                    (column.schema_str().into_owned(), column.org_table_str().into_owned(), column.org_name_str().into_owned()),
+                )
+                .await?;
+            if let Some(Some(srid)) = srid {
+                column_options.geometry_srids.insert(name.into_owned(), srid);
+            }
+        }
+        Ok(())
+    }
+
+    fn infer_json_types(columns: &[mysql_async::Column], sample: &[Row], column_options: &mut ColumnOptions) -> Result<()> {
+        for (i, column) in columns.iter().enumerate() {
+            let name = column.name_str();
+            if column.column_type() != ColumnType::MYSQL_TYPE_JSON || column_options.json_types.contains_key(name.as_ref()) {
+                continue;
+            }
+            let values = sample.iter().filter_map(|row| match row.as_ref(i) {
+                Some(Value::Bytes(bytes)) => Some(bytes.as_slice()),
+                _ => None,
+            });
+            if let Some(data_type) = ColumnData::infer_json_type(values)? {
+                column_options.json_types.insert(name.into_owned(), data_type);
+            }
+        }
+        Ok(())
+    }
+
+    fn get_columns(columns: &[Arc<ColumnData>], chunk_size: usize) -> Result<Vec<Column>> {
         columns
             .iter()
-            .map(|data| Column::from_data(data.clone()).unwrap())
+            .map(|data| if chunk_size > 0 { Column::with_capacity(data.clone(), chunk_size) } else { Column::from_data(data.clone()) })
             .collect()
     }
-}
 
-#[async_trait]
-impl DataReader for MysqlReader {
-    async fn read(&self, writer_factory: Box<dyn DataWriterFactory>) -> Result<()> {
+    ///Reads the rows into the writer, writing the rows an InvalidValuePolicy rejects to rejects.
+    async fn read_rows(&self, writer_factory: Box<dyn DataWriterFactory>, rejects: &mut RejectWriter) -> Result<()> {
         let mut conn = self.pool.get_conn().await?;
-        let query = format!("SELECT * FROM {}", self.table_name);
-        let mut stream = conn.exec_stream(query, mysql_async::Params::Empty).await?;
+        let mut column_options = self.column_options.clone();
+        //Preparing first exposes the result columns, so their metadata can be looked up before the connection is busy streaming
+        let query = self.get_query(&mut conn).await?;
+        let statement = conn.prep(query).await?;
+        let columns = statement.columns().to_vec();
+        if columns.iter().any(|column| column.column_type() == ColumnType::MYSQL_TYPE_TIMESTAMP) {
+            column_options.time_zone = Some(MysqlReader::get_time_zone(&mut conn, &self.column_options).await?);
+        }
+        MysqlReader::get_column_definitions(&mut conn, &columns, &mut column_options).await?;
+        MysqlReader::get_geometry_srids(&mut conn, &columns, &mut column_options).await?;
+        let mut stream = conn.exec_stream(&statement, self.params.clone()).await?;
+
+        //Rows used to infer JSON types are held back and replayed ahead of the rest of the stream
+        let mut sample = Vec::new();
+        let has_json = columns.iter().any(|column| column.column_type() == ColumnType::MYSQL_TYPE_JSON);
+        if has_json && column_options.json_inference_rows > 0 {
+            while sample.len() < column_options.json_inference_rows {
+                match stream.next().await {
+                    Some(row) => sample.push(row?),
+                    None => break,
+                }
+            }
+            MysqlReader::infer_json_types(&columns, &sample, &mut column_options)?;
+        }
+
         let mut column_data = Vec::new();
 
         let mut schema_vec = Vec::new();
-        for column in stream.columns().iter() {
-            let name = column.name_str().into_owned();
-            let flags = column.flags();
-            //Note that the not_null flag means null is not allowed (mysql NOT NULL) so it needs to be inverted for Arrow nullable
-            let nullable = !flags.contains(mysql_async::consts::ColumnFlags::NOT_NULL_FLAG);
-            let unsigned = flags.contains(mysql_async::consts::ColumnFlags::UNSIGNED_FLAG);
-            let column_type = column.column_type();
-            let data = Arc::new(ColumnData::new(name, unsigned, nullable, column_type)?);
+        for column in columns.iter() {
+            let data = Arc::new(ColumnData::new(column, &column_options)?);
             schema_vec.push(data.get_schema_field());
             column_data.push(data);
         }
 
-        let schema = Arc::new(Schema::new(schema_vec));
-
-        let (tx, mut rx) = mpsc::channel(self.chunk_size * 2);
+        let mut schema = Schema::new(schema_vec);
+        if let Some(geo_metadata) = ColumnData::get_geo_metadata(&column_data) {
+            schema = schema.with_metadata(HashMap::from([(GEO_METADATA_KEY.to_string(), geo_metadata)]));
+        }
+        let schema = Arc::new(schema);
+        rejects.header = columns.iter().map(|column| column.name_str().into_owned()).collect();
 
-        let writer_schema = schema.clone();
-        let write_task = tokio::task::spawn_blocking(move || {
-            let mut writer = writer_factory.create();
-            writer.setup(writer_schema)?;
-            while let Some(message) = rx.blocking_recv() {
-                match message {
-                    WriteMessage::Chunk(batch) => writer.write(&batch)?,
-                    WriteMessage::Finish => {
-                        writer.finish()?;
-                        return Ok(());
-                    }
-                    WriteMessage::Error => {
-                        writer.abort()?;
-                        return Ok(());
-                    }
-                }
-            }
-            //If this point is reached, sender channel closed too early, no Finish message was received, thus the end of the data stream was not reached and the database table cannot be properly backed up
-            bail!("End of data stream too early; improper backup");
-        });
+        let (tx, write_task) = spawn_writer(writer_factory, schema.clone(), self.chunk_size * 2);
 
+        let rows_stream = futures::stream::iter(sample.into_iter().map(Ok)).chain(stream);
+        let mut rows_stream = std::pin::pin!(rows_stream);
         loop {
-            let mut columns = MysqlReader::get_columns(&column_data);
+            let mut columns = MysqlReader::get_columns(&column_data, self.chunk_size)?;
 
             let mut rows: usize = 0;
-            while let Some(row_result) = stream.next().await {
+            while let Some(row_result) = rows_stream.next().await {
                 let row: Row = match row_result {
                     Ok(row) => row,
                     Err(e) => {
@@ -101,14 +399,23 @@ impl DataReader for MysqlReader {
                     }
                 };
 
-                for (i, v) in row.unwrap().into_iter().enumerate() {
+                let values = row.unwrap();
+                if columns.iter().zip(values.iter()).any(|(column, value)| Column::rejects(column, value)) {
+                    if let Err(e) = rejects.write(&values) {
+                        tx.send(WriteMessage::Error).await?;
+                        bail!(e);
+                    }
+                    continue;
+                }
+
+                for (i, v) in values.into_iter().enumerate() {
                     if let Err(e) = Column::push(&mut columns[i], v) {
                         tx.send(WriteMessage::Error).await?;
                         bail!(e);
                     }
                 }
 
-                rows = rows + 1;
+                rows += 1;
                 //If chunk size is > 0, and we have reached allocated chunk size (in rows) then break and the outer loop will send the chunk and then reiterate to find more chunks
                 if self.chunk_size > 0 && rows >= self.chunk_size {
                     break;
@@ -117,8 +424,13 @@ impl DataReader for MysqlReader {
 
             if rows > 0 {
                 //We have gathered either all data or a chunk
-                let batch_vec: Vec<Arc<dyn Array>> =
-                    columns.into_iter().map(|col| col.finish()).collect();
+                let batch_vec: Vec<Arc<dyn Array>> = match columns.into_iter().map(|col| col.finish()).collect() {
+                    Ok(batch_vec) => batch_vec,
+                    Err(e) => {
+                        tx.send(WriteMessage::Error).await?;
+                        bail!(e);
+                    }
+                };
                 let batch = arrow::array::RecordBatch::try_new(schema.clone(), batch_vec)?;
                 tx.send(WriteMessage::Chunk(batch)).await?;
             } else {
@@ -137,3 +449,76 @@ impl DataReader for MysqlReader {
         Ok(())
     }
 }
+
+#[async_trait]
+impl DataReader for MysqlReader {
+    async fn read(&self, writer_factory: Box<dyn DataWriterFactory>) -> Result<()> {
+        let mut rejects = RejectWriter::new(self.reject_path.clone());
+        //A reject file left by a previous run is removed up front, so that it is not kept when no row is rejected this time
+        rejects.abort()?;
+        if let Err(e) = self.read_rows(writer_factory, &mut rejects).await {
+            if let Err(abort_error) = rejects.abort() {
+                return Err(e.context(format!("Failed to remove the reject file: {abort_error:#}")));
+            }
+            return Err(e);
+        }
+        rejects.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn quotes_table_names() {
+        let pool = Pool::new("mysql://root@localhost/bank");
+        assert_eq!(MysqlReader::new(pool.clone(), "users".to_string(), 1000).query, "SELECT * FROM `users`");
+        assert_eq!(MysqlReader::new(pool, "order`s; DROP".to_string(), 1000).query, "SELECT * FROM `order``s; DROP`");
+    }
+
+    #[test]
+    fn stages_reject_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("users.rejects.csv");
+        fs::write(&path, "previous run").unwrap();
+        let mut rejects = RejectWriter::new(Some(path.clone()));
+        rejects.abort().unwrap();
+        assert!(!path.exists());
+        rejects.header = names(&["id", "name", "avatar"]);
+        rejects.write(&[Value::Int(1), Value::Bytes("José".as_bytes().to_vec()), Value::Bytes(vec![0xFF, 0x00])]).unwrap();
+        assert!(!path.exists());
+        rejects.finish().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "id,name,avatar\n1,José,0xFF00\n");
+        assert!(!directory.path().join("users.rejects.csv.temp").exists());
+        rejects.abort().unwrap();
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn selects_columns() {
+        let columns = names(&["id", "email", "phone_number", "created_at"]);
+        assert_eq!(ColumnSelection::All.select(&columns).unwrap(), columns);
+        assert_eq!(
+            ColumnSelection::Include(names(&["created_at", "id"])).select(&columns).unwrap(),
+            names(&["created_at", "id"])
+        );
+        assert_eq!(
+            ColumnSelection::Exclude(names(&["email", "phone_number"])).select(&columns).unwrap(),
+            names(&["id", "created_at"])
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_selections() {
+        let columns = names(&["id", "email"]);
+        assert!(ColumnSelection::Exclude(names(&["emial"])).select(&columns).is_err());
+        assert!(ColumnSelection::Include(names(&["id", "name"])).select(&columns).is_err());
+        assert!(ColumnSelection::Include(Vec::new()).select(&columns).is_err());
+        assert!(ColumnSelection::Exclude(columns.clone()).select(&columns).is_err());
+    }
+}
@@ -0,0 +1,404 @@
+use crate::data::sqlite_columns::SqliteType;
+use crate::readers::{DataReader, WriteMessage, spawn_writer};
+use crate::writers::DataWriterFactory;
+use anyhow::{Context, Result, bail};
+use arrow::{array::RecordBatch, datatypes::Schema};
+use async_trait::async_trait;
+use rusqlite::types::Value;
+use rusqlite::{Connection, OpenFlags, Statement};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+
+///Reads a table or the result of a query from a sqlite database file.
+///Column types come from the affinity of their declared types. Columns whose values are not all converted to text by their
+///affinity, such as INTEGER columns or those without a declared type, are scanned first to find the type that fits all of
+///their values.
+#[derive(Clone)]
+pub struct SqliteReader {
+    path: PathBuf,
+    table_name: Option<String>,
+    query: String,
+    chunk_size: usize,
+    inference_rows: usize,
+}
+
+impl SqliteReader {
+    pub fn new(path: PathBuf, table_name: String, chunk_size: usize) -> SqliteReader {
+        let query = format!("SELECT * FROM \"{}\"", table_name.replace('"', "\"\""));
+        SqliteReader {
+            path,
+            table_name: Some(table_name),
+            query,
+            chunk_size,
+            inference_rows: 0,
+        }
+    }
+
+    ///Reads the result of a query rather than a table. Every column of a query is nullable.
+    pub fn from_query(path: PathBuf, query: String, chunk_size: usize) -> SqliteReader {
+        SqliteReader {
+            path,
+            table_name: None,
+            query,
+            chunk_size,
+            inference_rows: 0,
+        }
+    }
+
+    ///Limits the scan of columns without a fixed type to this many leading rows rather than the whole table. Later values
+    ///that do not fit the type found fail the backup.
+    pub fn with_inference_rows(mut self, inference_rows: usize) -> SqliteReader {
+        self.inference_rows = inference_rows;
+        self
+    }
+
+    ///Scans the values of every column, leaving columns with only nulls as text. Affinity only prefers a class, so columns
+    ///with one start from it and are widened by the values stored in other classes.
+    fn scan_types(&self, statement: &mut Statement, types: &mut [Option<SqliteType>]) -> Result<()> {
+        let mut rows = statement.query([])?;
+        let mut count = 0;
+        while let Some(row) = rows.next()? {
+            for (i, sqlite_type) in types.iter_mut().enumerate() {
+                *sqlite_type = SqliteType::merge(*sqlite_type, row.get_ref(i)?);
+            }
+            count += 1;
+            if self.inference_rows > 0 && count >= self.inference_rows {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    ///Gets the names of the table's columns that cannot hold nulls, including an INTEGER PRIMARY KEY as it is the row id.
+    fn get_not_null_columns(&self, connection: &Connection) -> Result<HashSet<String>> {
+        let Some(table_name) = &self.table_name else {
+            return Ok(HashSet::new());
+        };
+        let mut statement = connection.prepare("SELECT name, type, \"notnull\", pk FROM pragma_table_info(?1)")?;
+        let columns = statement
+            .query_map([table_name], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, bool>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let row_id_key = columns.iter().filter(|(_, _, _, pk)| *pk > 0).count() == 1;
+        Ok(columns
+            .into_iter()
+            .filter(|(_, declared_type, not_null, pk)| {
+                *not_null || (row_id_key && *pk > 0 && declared_type.eq_ignore_ascii_case("INTEGER"))
+            })
+            .map(|(name, _, _, _)| name)
+            .collect())
+    }
+
+    fn send_rows(
+        &self,
+        statement: &mut Statement,
+        types: &[SqliteType],
+        schema: Arc<Schema>,
+        tx: &mpsc::Sender<WriteMessage>,
+    ) -> Result<()> {
+        let mut rows = statement.query([])?;
+        let mut columns: Vec<Vec<Value>> = vec![Vec::new(); types.len()];
+        let mut count = 0;
+        loop {
+            let row = rows.next()?;
+            if let Some(row) = row {
+                for (i, column) in columns.iter_mut().enumerate() {
+                    column.push(row.get(i)?);
+                }
+                count += 1;
+            }
+            //Chunks are sent once full, and whatever is left once the end of the rows is reached
+            let end = row.is_none();
+            if count > 0 && (end || (self.chunk_size > 0 && count >= self.chunk_size)) {
+                let mut arrays = Vec::with_capacity(types.len());
+                for (i, sqlite_type) in types.iter().enumerate() {
+                    let values = std::mem::take(&mut columns[i]);
+                    arrays.push(
+                        sqlite_type
+                            .build_array(&values)
+                            .with_context(|| format!("Could not read column {}", schema.field(i).name()))?,
+                    );
+                }
+                tx.blocking_send(WriteMessage::Chunk(RecordBatch::try_new(schema.clone(), arrays)?))?;
+                count = 0;
+            }
+            if end {
+                return Ok(());
+            }
+        }
+    }
+
+    fn read_blocking(&self, writer_factory: Box<dyn DataWriterFactory>) -> Result<()> {
+        let mut connection = Connection::open_with_flags(
+            &self.path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .with_context(|| format!("Failed to open sqlite database {}", self.path.display()))?;
+        //Scanning and reading happen in one transaction so that they see the same rows
+        let transaction = connection.transaction()?;
+        let mut statement = transaction.prepare(&self.query)?;
+        let declared_types: Vec<Option<String>> = statement
+            .columns()
+            .iter()
+            .map(|column| column.decl_type().map(str::to_string))
+            .collect();
+        let mut types: Vec<Option<SqliteType>> = declared_types
+            .iter()
+            .map(|declared_type| SqliteType::from_declared_type(declared_type.as_deref()))
+            .collect();
+        self.scan_types(&mut statement, &mut types)?;
+        let types: Vec<SqliteType> = types
+            .into_iter()
+            .map(|sqlite_type| sqlite_type.unwrap_or(SqliteType::Text))
+            .collect();
+
+        let not_null = self.get_not_null_columns(&transaction)?;
+        let fields: Vec<_> = statement
+            .column_names()
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| types[i].get_schema_field(name, declared_types[i].as_deref(), !not_null.contains(name)))
+            .collect();
+        let schema = Arc::new(Schema::new(fields));
+
+        let (tx, write_task) = spawn_writer(writer_factory, schema.clone(), 2);
+        match self.send_rows(&mut statement, &types, schema, &tx) {
+            Ok(()) => tx.blocking_send(WriteMessage::Finish)?,
+            Err(e) => {
+                tx.blocking_send(WriteMessage::Error)?;
+                bail!(e);
+            }
+        }
+
+        match Handle::current().block_on(write_task) {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => bail!(e),
+            Err(e) => bail!("Writer task failed: {e}."),
+        };
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DataReader for SqliteReader {
+    async fn read(&self, writer_factory: Box<dyn DataWriterFactory>) -> Result<()> {
+        //rusqlite is synchronous, so the whole table is read on a blocking thread
+        let reader = self.clone();
+        match tokio::task::spawn_blocking(move || reader.read_blocking(writer_factory)).await {
+            Ok(result) => result,
+            Err(e) => bail!("Reader task failed: {e}."),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writers::MemoryWriterFactory;
+    use arrow::array::AsArray;
+    use arrow::compute::concat_batches;
+    use arrow::datatypes::{DataType, Float64Type, Int64Type};
+    use std::path::Path;
+
+    fn database(directory: &Path) -> PathBuf {
+        let path = directory.join("state.sqlite");
+        let connection = Connection::open(&path).unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE devices (
+                    id INTEGER PRIMARY KEY,
+                    name VARCHAR(64) NOT NULL,
+                    battery REAL,
+                    reading NUMERIC,
+                    settings,
+                    firmware BLOB,
+                    seen_at DATETIME
+                );
+                INSERT INTO devices VALUES (1, 'north', 0.5, 12, 'on', x'0102', '2024-01-02 03:04:05');
+                INSERT INTO devices VALUES (2, 'south', 1, 12.5, 3, NULL, NULL);
+                INSERT INTO devices VALUES (3, 'east', NULL, NULL, NULL, x'03', NULL);",
+            )
+            .unwrap();
+        path
+    }
+
+    async fn read(reader: SqliteReader) -> Result<RecordBatch> {
+        let writer = MemoryWriterFactory::new();
+        reader.read(Box::new(writer.clone())).await?;
+        Ok(concat_batches(&writer.schema().unwrap(), &writer.batches().unwrap())?)
+    }
+
+    #[tokio::test]
+    async fn reads_table() {
+        let directory = tempfile::tempdir().unwrap();
+        let batch = read(SqliteReader::new(
+            database(directory.path()),
+            "devices".to_string(),
+            1000,
+        ))
+        .await
+        .unwrap();
+        let schema = batch.schema();
+        let types: Vec<&DataType> = schema.fields().iter().map(|field| field.data_type()).collect();
+        assert_eq!(
+            types,
+            vec![
+                &DataType::Int64,
+                &DataType::Utf8,
+                &DataType::Float64,
+                &DataType::Float64,
+                &DataType::Utf8,
+                &DataType::Binary,
+                &DataType::Utf8
+            ]
+        );
+        let nullable: Vec<bool> = schema.fields().iter().map(|field| field.is_nullable()).collect();
+        assert_eq!(nullable, vec![false, false, true, true, true, true, true]);
+        assert_eq!(schema.field(1).metadata()["sqlite.type"], "VARCHAR(64)");
+        assert!(schema.field(4).metadata().is_empty());
+
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(
+            batch.column(0).as_primitive::<Int64Type>().values().to_vec(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(batch.column(2).as_primitive::<Float64Type>().value(1), 1.0);
+        assert_eq!(batch.column(3).as_primitive::<Float64Type>().value(0), 12.0);
+        assert_eq!(
+            batch.column(4).as_string::<i32>().iter().collect::<Vec<_>>(),
+            vec![Some("on"), Some("3"), None]
+        );
+        assert_eq!(batch.column(5).as_binary::<i32>().value(0), &[1, 2]);
+        assert_eq!(batch.column(6).as_string::<i32>().value(0), "2024-01-02 03:04:05");
+    }
+
+    #[tokio::test]
+    async fn reads_in_chunks() {
+        let directory = tempfile::tempdir().unwrap();
+        let writer = MemoryWriterFactory::new();
+        let reader = SqliteReader::new(database(directory.path()), "devices".to_string(), 2);
+        reader.read(Box::new(writer.clone())).await.unwrap();
+        let rows: Vec<usize> = writer.batches().unwrap().iter().map(|batch| batch.num_rows()).collect();
+        assert_eq!(rows, vec![2, 1]);
+    }
+
+    #[tokio::test]
+    async fn reads_query() {
+        let directory = tempfile::tempdir().unwrap();
+        let query =
+            "SELECT name, battery * 100 AS percent, count(*) OVER () AS total FROM devices WHERE battery IS NOT NULL"
+                .to_string();
+        let batch = read(SqliteReader::from_query(database(directory.path()), query, 1000))
+            .await
+            .unwrap();
+        assert_eq!(batch.schema().field(0).data_type(), &DataType::Utf8);
+        assert!(batch.schema().field(0).is_nullable());
+        assert_eq!(
+            batch.column(1).as_primitive::<Float64Type>().values().to_vec(),
+            vec![50.0, 100.0]
+        );
+        assert_eq!(
+            batch.column(2).as_primitive::<Int64Type>().values().to_vec(),
+            vec![2, 2]
+        );
+    }
+
+    #[tokio::test]
+    async fn inference_rows_limit_scan() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = database(directory.path());
+        //The leading rows of reading are integers but a later one is real, which only the full scan finds
+        let query = "SELECT reading FROM devices WHERE reading IS NOT NULL ORDER BY reading".to_string();
+        let batch = read(SqliteReader::from_query(path.clone(), query.clone(), 1000))
+            .await
+            .unwrap();
+        assert_eq!(batch.schema().field(0).data_type(), &DataType::Float64);
+        let writer = MemoryWriterFactory::new();
+        let reader = SqliteReader::from_query(path, query, 1000).with_inference_rows(1);
+        assert!(reader.read(Box::new(writer.clone())).await.is_err());
+        assert!(writer.batches().is_none());
+    }
+
+    #[tokio::test]
+    async fn widens_mixed_storage() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = database(directory.path());
+        //Values that cannot be converted to the affinity of their column keep their own storage class
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE readings (count INTEGER, ratio REAL, level INTEGER, label TEXT, raw);
+                INSERT INTO readings VALUES (1, 0.5, 1, 'low', 1), (2.5, 'high', 'full', x'01', 'on');
+                INSERT INTO readings VALUES (NULL, NULL, NULL, NULL, x'02');",
+            )
+            .unwrap();
+        let batch = read(SqliteReader::new(path, "readings".to_string(), 1000)).await.unwrap();
+        assert_eq!(batch.schema().field(0).data_type(), &DataType::Float64);
+        assert_eq!(batch.schema().field(0).metadata()["sqlite.type"], "INTEGER");
+        assert_eq!(
+            batch.column(0).as_primitive::<Float64Type>().iter().collect::<Vec<_>>(),
+            vec![Some(1.0), Some(2.5), None]
+        );
+        assert_eq!(
+            batch.column(1).as_string::<i32>().iter().collect::<Vec<_>>(),
+            vec![Some("0.5"), Some("high"), None]
+        );
+        assert_eq!(
+            batch.column(2).as_string::<i32>().iter().collect::<Vec<_>>(),
+            vec![Some("1"), Some("full"), None]
+        );
+        //Any class mixed with blobs is written as binary, text and numbers as their UTF-8 text
+        assert_eq!(batch.schema().field(3).data_type(), &DataType::Binary);
+        assert_eq!(
+            batch.column(3).as_binary::<i32>().iter().collect::<Vec<_>>(),
+            vec![Some(b"low".as_slice()), Some(&[1]), None]
+        );
+        assert_eq!(
+            batch.column(4).as_binary::<i32>().iter().collect::<Vec<_>>(),
+            vec![Some(b"1".as_slice()), Some(b"on"), Some(&[2])]
+        );
+    }
+
+    #[tokio::test]
+    async fn fails_on_invalid_data() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = database(directory.path());
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE wrong (count INTEGER);
+                INSERT INTO wrong VALUES (1), (x'01');",
+            )
+            .unwrap();
+        //The blob is past the scanned rows, so the column stays an integer column it does not fit in
+        assert!(
+            read(SqliteReader::new(path.clone(), "wrong".to_string(), 1000).with_inference_rows(1))
+                .await
+                .is_err()
+        );
+        assert!(
+            read(SqliteReader::new(path, "missing".to_string(), 1000))
+                .await
+                .is_err()
+        );
+        assert!(
+            read(SqliteReader::new(
+                directory.path().join("missing.sqlite"),
+                "devices".to_string(),
+                1000
+            ))
+            .await
+            .is_err()
+        );
+        assert!(!directory.path().join("missing.sqlite").exists());
+    }
+}
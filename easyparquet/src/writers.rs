@@ -1,11 +1,16 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 use arrow::{array::RecordBatch, datatypes::Schema};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::Arc;
 
 pub trait DataWriter {
     fn setup(&mut self, schema: Arc<Schema>) -> Result<()>;
     fn write(&mut self, batch: &RecordBatch) -> Result<()>;
     fn finish(&mut self) -> Result<()>;
+    ///Discards the output, including output already committed by finish.
     fn abort(&mut self) -> Result<()>;
 }
 
@@ -13,5 +18,94 @@ pub trait DataWriterFactory: Send + Sync {
     fn create(&self) -> Box<dyn DataWriter>;
 }
 
+mod csv_writer;
+mod ipc_writer;
+mod json_writer;
+mod memory_writer;
+mod object_store_writer;
 mod parquet_writer;
-pub use parquet_writer::ParquetWriterFactory;
+mod partitioned_writer;
+mod tee_writer;
+#[cfg(test)]
+pub(crate) mod test_util;
+pub use csv_writer::{CsvOptions, CsvWriterFactory};
+pub use ipc_writer::{ArrowIpcOptions, ArrowIpcWriterFactory, IpcCompression};
+pub use json_writer::JsonLinesWriterFactory;
+pub use memory_writer::MemoryWriterFactory;
+pub use object_store_writer::ObjectStoreWriterFactory;
+pub(crate) use object_store_writer::parse_url;
+pub use parquet_writer::{BloomFilterOptions, ParquetOptions, ParquetWriterFactory};
+pub use partitioned_writer::{PartitionExpression, PartitionTransform, PartitionWriterFactory, PartitionedWriterFactory};
+pub use tee_writer::TeeWriterFactory;
+
+///Path with the extension appended to its full file name, so that names containing dots such as db.accounts keep them.
+pub(crate) fn append_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+///Removes a file, treating a file that was never written as already removed.
+pub(crate) fn remove_file(path: &Path) -> Result<()> {
+    if let Err(e) = fs::remove_file(path)
+        && e.kind() != ErrorKind::NotFound
+    {
+        bail!(e);
+    }
+    Ok(())
+}
+
+///File format a backup is written in, along with the options of its writer.
+#[derive(Debug, Clone)]
+pub enum OutputFormat {
+    Parquet(ParquetOptions),
+    Csv(CsvOptions),
+    ArrowIpc(ArrowIpcOptions),
+    JsonLines,
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Parquet(ParquetOptions::default())
+    }
+}
+
+impl OutputFormat {
+    ///Extension of the files written in this format, without the leading dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Parquet(_) => "parquet",
+            OutputFormat::Csv(options) if options.gzip => "csv.gz",
+            OutputFormat::Csv(_) => "csv",
+            OutputFormat::ArrowIpc(options) if options.stream => "arrows",
+            OutputFormat::ArrowIpc(_) => "arrow",
+            OutputFormat::JsonLines => "jsonl",
+        }
+    }
+
+    pub fn get_writer_factory(&self, file_path: PathBuf) -> Box<dyn DataWriterFactory> {
+        match self {
+            OutputFormat::Parquet(options) => Box::new(ParquetWriterFactory::new(file_path).with_options(options.clone())),
+            OutputFormat::Csv(options) => Box::new(CsvWriterFactory::new(file_path).with_options(options.clone())),
+            OutputFormat::ArrowIpc(options) => Box::new(ArrowIpcWriterFactory::new(file_path).with_options(*options)),
+            OutputFormat::JsonLines => Box::new(JsonLinesWriterFactory::new(file_path)),
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    ///Parses a format name into that format with default options.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "parquet" => Ok(OutputFormat::Parquet(ParquetOptions::default())),
+            "csv" => Ok(OutputFormat::Csv(CsvOptions::default())),
+            "arrow" => Ok(OutputFormat::ArrowIpc(ArrowIpcOptions::default())),
+            "arrow_stream" => Ok(OutputFormat::ArrowIpc(ArrowIpcOptions { stream: true, ..Default::default() })),
+            "jsonl" => Ok(OutputFormat::JsonLines),
+            _ => Err(format!("Unknown output format {s}, expected one of parquet, csv, arrow, arrow_stream or jsonl.")),
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use crate::writers::{DataWriter, DataWriterFactory, append_extension, remove_file};
+use anyhow::{Result, bail};
+use arrow::csv::{Writer, WriterBuilder};
+use arrow::{array::RecordBatch, datatypes::Schema};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::{fs::File, sync::Arc};
+
+///Formatting of the csv files written by a CsvWriter.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    ///Character fields containing the delimiter, quote or a line break are quoted with.
+    pub quote: u8,
+    ///Character quotes inside quoted fields are escaped with. When None, quotes are escaped by doubling them.
+    pub escape: Option<u8>,
+    ///Write the column names as the first line.
+    pub header: bool,
+    ///Text written for null values.
+    pub null_value: String,
+    ///chrono format strings for temporal columns, such as %d/%m/%Y. Unset formats use RFC 3339.
+    pub date_format: Option<String>,
+    pub time_format: Option<String>,
+    ///Format of timestamps without a time zone (mysql DATETIME).
+    pub timestamp_format: Option<String>,
+    ///Format of timestamps with a time zone (mysql TIMESTAMP).
+    pub timestamp_tz_format: Option<String>,
+    ///Compress the file with gzip.
+    pub gzip: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            header: true,
+            null_value: String::new(),
+            date_format: None,
+            time_format: None,
+            timestamp_format: None,
+            timestamp_tz_format: None,
+            gzip: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    fn get_writer_builder(&self) -> WriterBuilder {
+        let mut builder = WriterBuilder::new()
+            .with_delimiter(self.delimiter)
+            .with_quote(self.quote)
+            .with_header(self.header)
+            .with_null(self.null_value.clone());
+        if let Some(escape) = self.escape {
+            builder = builder.with_escape(escape).with_double_quote(false);
+        }
+        if let Some(format) = &self.date_format {
+            builder = builder.with_date_format(format.clone());
+        }
+        if let Some(format) = &self.time_format {
+            builder = builder.with_time_format(format.clone());
+        }
+        if let Some(format) = &self.timestamp_format {
+            builder = builder.with_timestamp_format(format.clone());
+        }
+        if let Some(format) = &self.timestamp_tz_format {
+            builder = builder.with_timestamp_tz_format(format.clone());
+        }
+        builder
+    }
+}
+
+///File a CsvWriter writes to, which needs finishing when compressed so the gzip trailer is written.
+enum CsvFile {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl CsvFile {
+    fn finish(self) -> Result<()> {
+        let mut file = match self {
+            CsvFile::Plain(file) => file,
+            CsvFile::Gzip(encoder) => encoder.finish()?,
+        };
+        file.flush()?;
+        file.into_inner()?.sync_all()?;
+        Ok(())
+    }
+}
+
+impl Write for CsvFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            CsvFile::Plain(file) => file.write(buf),
+            CsvFile::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            CsvFile::Plain(file) => file.flush(),
+            CsvFile::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
+pub struct CsvWriter {
+    file_path: PathBuf,
+    temp_path: PathBuf,
+    options: CsvOptions,
+    writer: Option<Writer<CsvFile>>,
+}
+
+impl CsvWriter {
+    pub fn new(file_path: PathBuf, options: CsvOptions) -> CsvWriter {
+        let temp_path = append_extension(&file_path, "temp");
+        CsvWriter {
+            file_path,
+            temp_path,
+            options,
+            writer: None,
+        }
+    }
+}
+
+impl DataWriter for CsvWriter {
+    fn setup(&mut self, schema: Arc<Schema>) -> Result<()> {
+        let file = BufWriter::new(File::create(&self.temp_path)?);
+        let file = if self.options.gzip {
+            CsvFile::Gzip(GzEncoder::new(file, Compression::default()))
+        } else {
+            CsvFile::Plain(file)
+        };
+        let mut writer = self.options.get_writer_builder().build(file);
+        //The header is written with the first batch, so an empty batch makes sure tables without rows still get one
+        writer.write(&RecordBatch::new_empty(schema))?;
+        self.writer = Some(writer);
+        Ok(())
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        if let Some(ref mut writer) = self.writer {
+            writer.write(batch)?;
+        } else {
+            bail!("No file handle.")
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            writer.into_inner().finish()?;
+        } else {
+            bail!("Invalid csv writer.");
+        }
+        fs::rename(&self.temp_path, &self.file_path)?;
+        Ok(())
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.writer = None;
+        remove_file(&self.temp_path)?;
+        remove_file(&self.file_path)
+    }
+}
+
+pub struct CsvWriterFactory {
+    file_path: PathBuf,
+    options: CsvOptions,
+}
+
+impl CsvWriterFactory {
+    pub fn new(file_path: PathBuf) -> Self {
+        Self {
+            file_path,
+            options: CsvOptions::default(),
+        }
+    }
+
+    pub fn with_options(mut self, options: CsvOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl DataWriterFactory for CsvWriterFactory {
+    fn create(&self) -> Box<dyn DataWriter> {
+        Box::new(CsvWriter::new(self.file_path.clone(), self.options.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writers::test_util::{self, write};
+    use arrow::array::{Date32Array, Int64Array, StringArray, TimestampMicrosecondArray};
+    use arrow::datatypes::{DataType, Field, TimeUnit};
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    //Values that need quoting, nulls, dates and timestamps, so that every formatting option shows in the output
+    fn formatted_batch() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("joined", DataType::Date32, true),
+            Field::new("seen", DataType::Timestamp(TimeUnit::Microsecond, None), true),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec![Some("Smith; \"Jo\""), None])),
+                Arc::new(Date32Array::from(vec![Some(19_000), None])),
+                Arc::new(TimestampMicrosecondArray::from(vec![Some(1_700_000_000_000_000), None])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn default_format() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("table.csv");
+        write(&CsvWriterFactory::new(path.clone()), formatted_batch().schema(), &[formatted_batch()]).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "id,name,joined,seen\n1,\"Smith; \"\"Jo\"\"\",2022-01-08,2023-11-14T22:13:20\n2,,,\n"
+        );
+        assert!(!directory.path().join("table.csv.temp").exists());
+    }
+
+    #[test]
+    fn configured_format() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("table.csv");
+        let options = CsvOptions {
+            delimiter: b';',
+            quote: b'\'',
+            escape: Some(b'\\'),
+            header: false,
+            null_value: String::from("NULL"),
+            date_format: Some(String::from("%d/%m/%Y")),
+            timestamp_format: Some(String::from("%Y-%m-%d %H:%M")),
+            ..Default::default()
+        };
+        let factory = CsvWriterFactory::new(path.clone()).with_options(options);
+        write(&factory, formatted_batch().schema(), &[formatted_batch()]).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "1;'Smith; \"Jo\"';08/01/2022;2023-11-14 22:13\n2;NULL;NULL;NULL\n"
+        );
+    }
+
+    #[test]
+    fn header_without_rows() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("table.csv");
+        write(&CsvWriterFactory::new(path.clone()), formatted_batch().schema(), &[]).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "id,name,joined,seen\n");
+    }
+
+    #[test]
+    fn gzip() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("table.csv.gz");
+        let options = CsvOptions { gzip: true, ..Default::default() };
+        let factory = CsvWriterFactory::new(path.clone()).with_options(options);
+        write(&factory, formatted_batch().schema(), &[formatted_batch(), formatted_batch()]).unwrap();
+        let mut contents = String::new();
+        GzDecoder::new(File::open(&path).unwrap()).read_to_string(&mut contents).unwrap();
+        assert_eq!(contents.lines().count(), 5);
+        assert!(!directory.path().join("table.csv.gz.temp").exists());
+    }
+
+    #[test]
+    fn abort_removes_files() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("table.csv");
+        let mut writer = CsvWriterFactory::new(path.clone()).create();
+        writer.setup(test_util::batch(2).schema()).unwrap();
+        writer.write(&test_util::batch(2)).unwrap();
+        writer.abort().unwrap();
+        assert!(!path.exists());
+        assert!(!directory.path().join("table.csv.temp").exists());
+    }
+}
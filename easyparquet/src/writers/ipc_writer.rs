@@ -0,0 +1,171 @@
+use crate::writers::{DataWriter, DataWriterFactory, append_extension, remove_file};
+use anyhow::{Result, bail};
+use arrow::ipc::CompressionType;
+use arrow::ipc::writer::{FileWriter, IpcWriteOptions, StreamWriter};
+use arrow::{array::RecordBatch, datatypes::Schema};
+use std::fs;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::{fs::File, sync::Arc};
+
+///Codec the record batch bodies of an Arrow IPC file are compressed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpcCompression {
+    Lz4,
+    Zstd,
+}
+
+impl FromStr for IpcCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "lz4" => Ok(IpcCompression::Lz4),
+            "zstd" => Ok(IpcCompression::Zstd),
+            _ => Err(format!("Unknown Arrow IPC compression {s}, expected lz4 or zstd.")),
+        }
+    }
+}
+
+///Options of the files written by an ArrowIpcWriter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArrowIpcOptions {
+    ///Write the IPC streaming format instead of the random access file format (Feather v2).
+    pub stream: bool,
+    pub compression: Option<IpcCompression>,
+}
+
+impl ArrowIpcOptions {
+    fn get_write_options(&self) -> Result<IpcWriteOptions> {
+        let compression = self.compression.map(|compression| match compression {
+            IpcCompression::Lz4 => CompressionType::LZ4_FRAME,
+            IpcCompression::Zstd => CompressionType::ZSTD,
+        });
+        Ok(IpcWriteOptions::default().try_with_compression(compression)?)
+    }
+}
+
+enum IpcWriter {
+    File(FileWriter<BufWriter<File>>),
+    Stream(StreamWriter<BufWriter<File>>),
+}
+
+pub struct ArrowIpcWriter {
+    file_path: PathBuf,
+    temp_path: PathBuf,
+    options: ArrowIpcOptions,
+    writer: Option<IpcWriter>,
+}
+
+impl ArrowIpcWriter {
+    pub fn new(file_path: PathBuf, options: ArrowIpcOptions) -> ArrowIpcWriter {
+        let temp_path = append_extension(&file_path, "temp");
+        ArrowIpcWriter {
+            file_path,
+            temp_path,
+            options,
+            writer: None,
+        }
+    }
+}
+
+impl DataWriter for ArrowIpcWriter {
+    fn setup(&mut self, schema: Arc<Schema>) -> Result<()> {
+        let write_options = self.options.get_write_options()?;
+        let file = BufWriter::new(File::create(&self.temp_path)?);
+        self.writer = Some(if self.options.stream {
+            IpcWriter::Stream(StreamWriter::try_new_with_options(file, &schema, write_options)?)
+        } else {
+            IpcWriter::File(FileWriter::try_new_with_options(file, &schema, write_options)?)
+        });
+        Ok(())
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        match self.writer {
+            Some(IpcWriter::File(ref mut writer)) => writer.write(batch)?,
+            Some(IpcWriter::Stream(ref mut writer)) => writer.write(batch)?,
+            None => bail!("No file handle."),
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let file = match self.writer.take() {
+            Some(IpcWriter::File(writer)) => writer.into_inner()?,
+            Some(IpcWriter::Stream(writer)) => writer.into_inner()?,
+            None => bail!("Invalid Arrow IPC writer."),
+        };
+        file.into_inner()?.sync_all()?;
+        fs::rename(&self.temp_path, &self.file_path)?;
+        Ok(())
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.writer = None;
+        remove_file(&self.temp_path)?;
+        remove_file(&self.file_path)
+    }
+}
+
+pub struct ArrowIpcWriterFactory {
+    file_path: PathBuf,
+    options: ArrowIpcOptions,
+}
+
+impl ArrowIpcWriterFactory {
+    pub fn new(file_path: PathBuf) -> Self {
+        Self {
+            file_path,
+            options: ArrowIpcOptions::default(),
+        }
+    }
+
+    pub fn with_options(mut self, options: ArrowIpcOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl DataWriterFactory for ArrowIpcWriterFactory {
+    fn create(&self) -> Box<dyn DataWriter> {
+        Box::new(ArrowIpcWriter::new(self.file_path.clone(), self.options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writers::test_util::{batch, write};
+    use arrow::ipc::reader::{FileReader, StreamReader};
+    use test_case::test_case;
+
+    #[test_case(false, None; "File")]
+    #[test_case(true, None; "Stream")]
+    #[test_case(false, Some(IpcCompression::Lz4); "File with lz4")]
+    #[test_case(true, Some(IpcCompression::Zstd); "Stream with zstd")]
+    fn round_trip(stream: bool, compression: Option<IpcCompression>) {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("table.arrow");
+        let options = ArrowIpcOptions { stream, compression };
+        let factory = ArrowIpcWriterFactory::new(path.clone()).with_options(options);
+        write(&factory, batch(0).schema(), &[batch(100), batch(100)]).unwrap();
+        assert!(!directory.path().join("table.arrow.temp").exists());
+
+        let file = File::open(&path).unwrap();
+        let batches: Vec<RecordBatch> = if stream {
+            StreamReader::try_new(file, None).unwrap().collect::<Result<_, _>>().unwrap()
+        } else {
+            FileReader::try_new(file, None).unwrap().collect::<Result<_, _>>().unwrap()
+        };
+        assert_eq!(batches, vec![batch(100), batch(100)]);
+    }
+
+    #[test]
+    fn parse_compression() {
+        assert_eq!("LZ4".parse(), Ok(IpcCompression::Lz4));
+        assert_eq!("zstd".parse(), Ok(IpcCompression::Zstd));
+        assert!("gzip".parse::<IpcCompression>().is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::writers::{DataWriter, DataWriterFactory, append_extension, remove_file};
+use anyhow::{Result, bail};
+use arrow::json::{LineDelimitedWriter, WriterBuilder};
+use arrow::{array::RecordBatch, datatypes::Schema};
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::{fs::File, sync::Arc};
+
+///Writes one JSON object per row, separated by newlines.
+///Null values are written as explicit nulls so that every line has every column.
+pub struct JsonLinesWriter {
+    file_path: PathBuf,
+    temp_path: PathBuf,
+    writer: Option<LineDelimitedWriter<BufWriter<File>>>,
+}
+
+impl JsonLinesWriter {
+    pub fn new(file_path: PathBuf) -> JsonLinesWriter {
+        let temp_path = append_extension(&file_path, "temp");
+        JsonLinesWriter {
+            file_path,
+            temp_path,
+            writer: None,
+        }
+    }
+}
+
+impl DataWriter for JsonLinesWriter {
+    fn setup(&mut self, _schema: Arc<Schema>) -> Result<()> {
+        let file = BufWriter::new(File::create(&self.temp_path)?);
+        self.writer = Some(WriterBuilder::new().with_explicit_nulls(true).build(file));
+        Ok(())
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        if let Some(ref mut writer) = self.writer {
+            writer.write(batch)?;
+        } else {
+            bail!("No file handle.")
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.finish()?;
+            let mut file = writer.into_inner();
+            file.flush()?;
+            file.into_inner()?.sync_all()?;
+        } else {
+            bail!("Invalid JSON lines writer.");
+        }
+        fs::rename(&self.temp_path, &self.file_path)?;
+        Ok(())
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.writer = None;
+        remove_file(&self.temp_path)?;
+        remove_file(&self.file_path)
+    }
+}
+
+pub struct JsonLinesWriterFactory {
+    file_path: PathBuf,
+}
+
+impl JsonLinesWriterFactory {
+    pub fn new(file_path: PathBuf) -> Self {
+        Self { file_path }
+    }
+}
+
+impl DataWriterFactory for JsonLinesWriterFactory {
+    fn create(&self) -> Box<dyn DataWriter> {
+        Box::new(JsonLinesWriter::new(self.file_path.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field};
+
+    #[test]
+    fn writes_lines() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("table.jsonl");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from(vec![1, 2])), Arc::new(StringArray::from(vec![Some("Jo"), None]))],
+        )
+        .unwrap();
+        let mut writer = JsonLinesWriterFactory::new(path.clone()).create();
+        writer.setup(schema).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"id\":1,\"name\":\"Jo\"}\n{\"id\":2,\"name\":null}\n");
+        assert!(!directory.path().join("table.jsonl.temp").exists());
+    }
+}
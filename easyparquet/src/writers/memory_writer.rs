@@ -0,0 +1,133 @@
+use crate::writers::{DataWriter, DataWriterFactory};
+use anyhow::{Result, bail};
+use arrow::{array::RecordBatch, datatypes::Schema};
+use std::sync::{Arc, Mutex};
+
+//Data committed by the last writer to finish
+#[derive(Default)]
+struct MemoryOutput {
+    schema: Option<Arc<Schema>>,
+    batches: Option<Vec<RecordBatch>>,
+}
+
+///Collects record batches in memory. They only become visible through the factory once finish is called, and an abort
+///discards them along with any output committed before.
+pub struct MemoryWriter {
+    output: Arc<Mutex<MemoryOutput>>,
+    schema: Option<Arc<Schema>>,
+    batches: Vec<RecordBatch>,
+}
+
+impl MemoryWriter {
+    fn new(output: Arc<Mutex<MemoryOutput>>) -> MemoryWriter {
+        MemoryWriter {
+            output,
+            schema: None,
+            batches: Vec::new(),
+        }
+    }
+}
+
+impl DataWriter for MemoryWriter {
+    fn setup(&mut self, schema: Arc<Schema>) -> Result<()> {
+        self.schema = Some(schema);
+        Ok(())
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        if self.schema.is_none() {
+            bail!("Memory writer is not set up.");
+        }
+        self.batches.push(batch.clone());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let Some(schema) = self.schema.take() else {
+            bail!("Invalid memory writer.");
+        };
+        let Ok(mut output) = self.output.lock() else {
+            bail!("Memory writer output is poisoned.");
+        };
+        output.schema = Some(schema);
+        output.batches = Some(std::mem::take(&mut self.batches));
+        Ok(())
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.schema = None;
+        self.batches.clear();
+        let Ok(mut output) = self.output.lock() else {
+            bail!("Memory writer output is poisoned.");
+        };
+        *output = MemoryOutput::default();
+        Ok(())
+    }
+}
+
+///Creates writers collecting batches in memory. Clones share their output, so a clone can be kept to retrieve the batches
+///after the factory itself has been passed to a reader.
+#[derive(Clone, Default)]
+pub struct MemoryWriterFactory {
+    output: Arc<Mutex<MemoryOutput>>,
+}
+
+impl MemoryWriterFactory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Batches written by the last writer to finish, or None if none has finished or the output was aborted.
+    pub fn batches(&self) -> Option<Vec<RecordBatch>> {
+        self.output.lock().ok()?.batches.clone()
+    }
+
+    ///Schema of the batches written by the last writer to finish.
+    pub fn schema(&self) -> Option<Arc<Schema>> {
+        self.output.lock().ok()?.schema.clone()
+    }
+}
+
+impl DataWriterFactory for MemoryWriterFactory {
+    fn create(&self) -> Box<dyn DataWriter> {
+        Box::new(MemoryWriter::new(self.output.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writers::test_util::batch;
+
+    #[test]
+    fn batches_visible_after_finish() {
+        let factory = MemoryWriterFactory::new();
+        let mut writer = factory.create();
+        writer.setup(batch(2).schema()).unwrap();
+        writer.write(&batch(2)).unwrap();
+        writer.write(&batch(2)).unwrap();
+        assert!(factory.batches().is_none());
+        writer.finish().unwrap();
+        assert_eq!(factory.batches().unwrap(), vec![batch(2), batch(2)]);
+        assert_eq!(factory.schema().unwrap(), batch(2).schema());
+    }
+
+    #[test]
+    fn abort_discards_batches() {
+        let factory = MemoryWriterFactory::new();
+        let mut writer = factory.create();
+        writer.setup(batch(2).schema()).unwrap();
+        writer.write(&batch(2)).unwrap();
+        writer.finish().unwrap();
+        writer.abort().unwrap();
+        assert!(factory.batches().is_none());
+        assert!(factory.schema().is_none());
+    }
+
+    #[test]
+    fn write_without_setup() {
+        let mut writer = MemoryWriterFactory::new().create();
+        assert!(writer.write(&batch(2)).is_err());
+        assert!(writer.finish().is_err());
+    }
+}
@@ -0,0 +1,418 @@
+use crate::writers::{DataWriter, DataWriterFactory, ParquetOptions};
+use anyhow::{Context, Result, bail};
+use arrow::{array::RecordBatch, datatypes::Schema};
+use object_store::path::Path;
+use object_store::{MultipartUpload, ObjectStore, PutPayload};
+use parquet::arrow::ArrowWriter;
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tokio::task::{JoinError, JoinSet};
+use url::Url;
+
+//Number of parts uploaded at once before writes wait for one to complete, which bounds the memory held by a writer
+const MAX_CONCURRENT_UPLOADS: usize = 8;
+//Size of every part but the last, the minimum most object stores accept and the same for all parts as some require
+const PART_SIZE: usize = 5 * 1024 * 1024;
+
+///Blocking std::io::Write over a multipart upload, so that the synchronous ArrowWriter can stream to an object store.
+///Parts are uploaded in the background on the runtime the writer was set up on. The upload stays owned by the writer until
+///it is completed, so that it can still be aborted whichever step fails.
+struct MultipartWriter {
+    upload: Box<dyn MultipartUpload>,
+    buffer: Vec<u8>,
+    parts: JoinSet<object_store::Result<()>>,
+    handle: Handle,
+}
+
+impl MultipartWriter {
+    fn new(upload: Box<dyn MultipartUpload>, handle: Handle) -> MultipartWriter {
+        MultipartWriter {
+            upload,
+            buffer: Vec::new(),
+            parts: JoinSet::new(),
+            handle,
+        }
+    }
+
+    fn upload_part(&mut self) -> Result<()> {
+        //A part that already failed stops the upload before more data is sent
+        while let Some(result) = self.parts.try_join_next() {
+            check_part(result)?;
+        }
+        while self.parts.len() >= MAX_CONCURRENT_UPLOADS {
+            self.wait_for_part()?;
+        }
+        //Stores may start the request as soon as the part is created, so that needs to happen inside the runtime
+        let _runtime = self.handle.enter();
+        let part = self.upload.put_part(PutPayload::from(std::mem::take(&mut self.buffer)));
+        self.parts.spawn_on(part, &self.handle);
+        Ok(())
+    }
+
+    fn wait_for_part(&mut self) -> Result<()> {
+        if let Some(result) = self.handle.block_on(self.parts.join_next()) {
+            check_part(result)?;
+        }
+        Ok(())
+    }
+
+    fn complete(&mut self) -> Result<()> {
+        if !self.buffer.is_empty() {
+            self.upload_part()?;
+        }
+        while !self.parts.is_empty() {
+            self.wait_for_part()?;
+        }
+        self.handle.block_on(self.upload.complete())?;
+        Ok(())
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.handle.block_on(async {
+            self.parts.shutdown().await;
+            self.upload.abort().await
+        })?;
+        Ok(())
+    }
+}
+
+fn check_part(result: Result<object_store::Result<()>, JoinError>) -> Result<()> {
+    result.context("Part upload task failed.")?.context("Failed to upload part.")
+}
+
+impl Write for MultipartWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let length = buf.len().min(PART_SIZE - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..length]);
+        if self.buffer.len() == PART_SIZE {
+            self.upload_part().map_err(std::io::Error::other)?;
+        }
+        Ok(length)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+///Streams a Parquet file to an object store through a multipart upload.
+///The object only becomes visible once the upload is completed by finish, so readers never see a partial backup.
+///DataWriter methods block on the tokio runtime and must be called from a blocking thread, such as one started by spawn_blocking.
+pub struct ObjectStoreWriter {
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+    options: ParquetOptions,
+    writer: Option<ArrowWriter<MultipartWriter>>,
+    committed: bool,
+}
+
+impl ObjectStoreWriter {
+    pub fn new(store: Arc<dyn ObjectStore>, path: Path, options: ParquetOptions) -> ObjectStoreWriter {
+        ObjectStoreWriter {
+            store,
+            path,
+            options,
+            writer: None,
+            committed: false,
+        }
+    }
+}
+
+impl DataWriter for ObjectStoreWriter {
+    fn setup(&mut self, schema: Arc<Schema>) -> Result<()> {
+        if self.options.max_rows_per_file.is_some() || self.options.max_bytes_per_file.is_some() {
+            bail!("Rolling Parquet files are not supported when writing to an object store.");
+        }
+        let handle = Handle::try_current().context("Object store writers need a tokio runtime.")?;
+        let properties = self.options.get_writer_properties(&schema)?;
+        let upload = handle
+            .block_on(self.store.put_multipart(&self.path))
+            .with_context(|| format!("Failed to start uploading {}", self.path))?;
+        self.writer = Some(ArrowWriter::try_new(MultipartWriter::new(upload, handle), schema, Some(properties))?);
+        Ok(())
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        if let Some(ref mut writer) = self.writer {
+            writer.write(batch)?;
+        } else {
+            bail!("No file handle.")
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let Some(ref mut writer) = self.writer else {
+            bail!("Invalid object store writer.");
+        };
+        //The writer is only dropped once the upload is completed, so that abort can cancel it if completing fails
+        writer.finish()?;
+        writer
+            .inner_mut()
+            .complete()
+            .with_context(|| format!("Failed to complete uploading {}", self.path))?;
+        self.writer = None;
+        self.committed = true;
+        Ok(())
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        //Dropping the writer discards buffered rows, only the parts already uploaded need cleaning up
+        if let Some(mut writer) = self.writer.take() {
+            writer.inner_mut().abort()?;
+        }
+        if self.committed {
+            let handle = Handle::try_current().context("Object store writers need a tokio runtime.")?;
+            match handle.block_on(self.store.delete(&self.path)) {
+                Ok(()) | Err(object_store::Error::NotFound { .. }) => self.committed = false,
+                Err(e) => bail!(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct ObjectStoreWriterFactory {
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+    options: ParquetOptions,
+}
+
+impl ObjectStoreWriterFactory {
+    pub fn new(store: Arc<dyn ObjectStore>, path: Path) -> Self {
+        Self {
+            store,
+            path,
+            options: ParquetOptions::default(),
+        }
+    }
+
+    ///Creates a factory writing to the object at url, such as s3://bucket/prefix/users.parquet or file:///backups/users.parquet.
+    ///store_options configure the backend using object_store's keys, for example aws_endpoint, aws_access_key_id,
+    ///aws_secret_access_key and aws_allow_http for a MinIO server.
+    pub fn from_url(url: &str, store_options: &HashMap<String, String>) -> Result<Self> {
+        let (store, path) = parse_url(url, store_options)?;
+        Ok(Self::new(store, path))
+    }
+
+    pub fn with_options(mut self, options: ParquetOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+///Gets the object store and path within it a URL refers to.
+pub fn parse_url(url: &str, store_options: &HashMap<String, String>) -> Result<(Arc<dyn ObjectStore>, Path)> {
+    let parsed = Url::parse(url).with_context(|| format!("Invalid object store URL {url}"))?;
+    let (store, path) = object_store::parse_url_opts(&parsed, store_options.iter())
+        .with_context(|| format!("Unsupported object store URL {url}"))?;
+    Ok((Arc::from(store), path))
+}
+
+impl DataWriterFactory for ObjectStoreWriterFactory {
+    fn create(&self) -> Box<dyn DataWriter> {
+        Box::new(ObjectStoreWriter::new(self.store.clone(), self.path.clone(), self.options.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::readers::{DataReader, MemoryReader};
+    use crate::writers::test_util::batch;
+    use arrow::compute::concat_batches;
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use futures::stream::BoxStream;
+    use object_store::local::LocalFileSystem;
+    use object_store::memory::InMemory;
+    use object_store::{GetOptions, GetResult, ListResult, ObjectMeta, PutMultipartOptions, PutOptions, PutResult, UploadPart};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    //In-memory store whose multipart uploads fail to upload any part, counting the uploads neither completed nor aborted
+    #[derive(Debug, Default)]
+    struct FailingStore {
+        inner: InMemory,
+        pending: Arc<AtomicUsize>,
+    }
+
+    impl std::fmt::Display for FailingStore {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "FailingStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for FailingStore {
+        async fn put_opts(&self, location: &Path, payload: PutPayload, opts: PutOptions) -> object_store::Result<PutResult> {
+            self.inner.put_opts(location, payload, opts).await
+        }
+
+        async fn put_multipart_opts(&self, location: &Path, opts: PutMultipartOptions) -> object_store::Result<Box<dyn MultipartUpload>> {
+            let upload = self.inner.put_multipart_opts(location, opts).await?;
+            self.pending.fetch_add(1, Ordering::SeqCst);
+            Ok(Box::new(FailingUpload { inner: upload, pending: self.pending.clone() }))
+        }
+
+        async fn get_opts(&self, location: &Path, options: GetOptions) -> object_store::Result<GetResult> {
+            self.inner.get_opts(location, options).await
+        }
+
+        async fn delete(&self, location: &Path) -> object_store::Result<()> {
+            self.inner.delete(location).await
+        }
+
+        fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+            self.inner.list(prefix)
+        }
+
+        async fn list_with_delimiter(&self, prefix: Option<&Path>) -> object_store::Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+            self.inner.copy_if_not_exists(from, to).await
+        }
+    }
+
+    #[derive(Debug)]
+    struct FailingUpload {
+        inner: Box<dyn MultipartUpload>,
+        pending: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl MultipartUpload for FailingUpload {
+        fn put_part(&mut self, _data: PutPayload) -> UploadPart {
+            Box::pin(async { Err(object_store::Error::Generic { store: "FailingStore", source: "Part upload failed.".into() }) })
+        }
+
+        async fn complete(&mut self) -> object_store::Result<PutResult> {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+            self.inner.complete().await
+        }
+
+        async fn abort(&mut self) -> object_store::Result<()> {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+            self.inner.abort().await
+        }
+    }
+
+    //Runs the writer on a blocking thread as the readers do, finishing it or aborting it afterwards
+    async fn write(factory: ObjectStoreWriterFactory, batches: Vec<RecordBatch>, finish: bool) -> Result<()> {
+        tokio::task::spawn_blocking(move || {
+            let mut writer = factory.create();
+            writer.setup(batch(0).schema())?;
+            for batch in batches.iter() {
+                writer.write(batch)?;
+            }
+            if finish { writer.finish() } else { writer.abort() }
+        })
+        .await?
+    }
+
+    //Reads the file back as a single batch, since the reader does not keep the boundaries of the batches written
+    fn read(bytes: Bytes) -> RecordBatch {
+        let batches: Vec<RecordBatch> = ParquetRecordBatchReaderBuilder::try_new(bytes)
+            .unwrap()
+            .build()
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        concat_batches(&batch(0).schema(), &batches).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn writes_to_memory() {
+        let store = Arc::new(InMemory::new());
+        let path = Path::from("backups/users.parquet");
+        let factory = ObjectStoreWriterFactory::new(store.clone(), path.clone());
+        write(factory, vec![batch(10), batch(5)], true).await.unwrap();
+        let bytes = store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(read(bytes), concat_batches(&batch(0).schema(), &[batch(10), batch(5)]).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn uploads_multiple_parts() {
+        let store = Arc::new(InMemory::new());
+        let path = Path::from("ledger.parquet");
+        //Small row groups flush data to the upload as it is written rather than all at once in finish
+        let options = ParquetOptions { max_row_group_size: Some(10_000), ..Default::default() };
+        let factory = ObjectStoreWriterFactory::new(store.clone(), path.clone()).with_options(options);
+        let batches: Vec<RecordBatch> = (0..30).map(|_| batch(10_000)).collect();
+        write(factory, batches, true).await.unwrap();
+        let meta = store.head(&path).await.unwrap();
+        assert!(meta.size > 5 * 1024 * 1024);
+        let bytes = store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(read(bytes).num_rows(), 300_000);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failed_upload_is_aborted() {
+        let store = Arc::new(FailingStore::default());
+        let path = Path::from("ledger.parquet");
+        let options = ParquetOptions { max_row_group_size: Some(10_000), ..Default::default() };
+        let factory = ObjectStoreWriterFactory::new(store.clone(), path.clone()).with_options(options);
+        let batches: Vec<RecordBatch> = (0..30).map(|_| batch(10_000)).collect();
+        let reader = MemoryReader::new(batch(0).schema(), batches);
+        assert!(reader.read(Box::new(factory)).await.is_err());
+        assert!(store.head(&path).await.is_err());
+        assert_eq!(store.pending.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn abort_leaves_nothing() {
+        let store = Arc::new(InMemory::new());
+        let path = Path::from("users.parquet");
+        let factory = ObjectStoreWriterFactory::new(store.clone(), path.clone());
+        write(factory, vec![batch(10)], false).await.unwrap();
+        assert!(store.head(&path).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn abort_after_finish_deletes_object() {
+        let store = Arc::new(InMemory::new());
+        let path = Path::from("users.parquet");
+        let factory = ObjectStoreWriterFactory::new(store.clone(), path.clone());
+        tokio::task::spawn_blocking(move || {
+            let mut writer = factory.create();
+            writer.setup(batch(0).schema())?;
+            writer.write(&batch(10))?;
+            writer.finish()?;
+            writer.abort()
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(store.head(&path).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn writes_to_local_url() {
+        let directory = tempfile::tempdir().unwrap();
+        let file_path = directory.path().join("users.parquet");
+        let url = Url::from_file_path(&file_path).unwrap();
+        let factory = ObjectStoreWriterFactory::from_url(url.as_str(), &HashMap::new()).unwrap();
+        write(factory, vec![batch(3)], true).await.unwrap();
+        let bytes = Bytes::from(std::fs::read(&file_path).unwrap());
+        assert_eq!(read(bytes), batch(3));
+
+        let store = Arc::new(LocalFileSystem::new_with_prefix(directory.path()).unwrap());
+        let factory = ObjectStoreWriterFactory::new(store, Path::from("aborted.parquet"));
+        write(factory, vec![batch(3)], false).await.unwrap();
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn invalid_url() {
+        assert!(ObjectStoreWriterFactory::from_url("not a url", &HashMap::new()).is_err());
+        assert!(ObjectStoreWriterFactory::from_url("ftp://host/users.parquet", &HashMap::new()).is_err());
+    }
+}
@@ -1,86 +1,572 @@
-use crate::writers::{DataWriter, DataWriterFactory};
-use anyhow::{Result, bail};
+use crate::encryption::EncryptionOptions;
+use crate::writers::{DataWriter, DataWriterFactory, append_extension, remove_file};
+use anyhow::{Context, Result, bail};
 use arrow::{array::RecordBatch, datatypes::Schema};
-use parquet::arrow::ArrowWriter;
+use parquet::arrow::{ArrowSchemaConverter, ArrowWriter};
+use parquet::basic::Compression;
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::{EnabledStatistics, WriterProperties, WriterVersion};
+use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::fs;
 use std::io::ErrorKind;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{fs::File, sync::Arc};
 
+///Bloom filter written for a column so point lookups can skip row groups that do not contain a value.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BloomFilterOptions {
+    ///Expected number of distinct values in a row group, which sizes the filter. Defaults to 1,000,000.
+    pub ndv: Option<u64>,
+    ///Desired false positive probability, between 0 and 1 exclusive. Defaults to 0.05.
+    pub fpp: Option<f64>,
+}
+
+///Properties of the Parquet files written by a ParquetWriter. Properties left unset use the parquet crate's defaults.
+#[derive(Debug, Clone, Default)]
+pub struct ParquetOptions {
+    ///Compression codec and level, such as Compression::ZSTD(ZstdLevel::try_new(3)?).
+    pub compression: Option<Compression>,
+    ///Maximum number of rows in a row group.
+    pub max_row_group_size: Option<usize>,
+    ///Best effort maximum size of a data page in bytes.
+    pub data_page_size: Option<usize>,
+    ///Whether dictionary encoding is used for columns without an entry in column_dictionary.
+    pub dictionary_enabled: Option<bool>,
+    ///Per-column overrides of dictionary_enabled, keyed by column name. Applies to every leaf of nested columns.
+    pub column_dictionary: HashMap<String, bool>,
+    ///Level statistics are written at: none, per chunk or per page.
+    pub statistics: Option<EnabledStatistics>,
+    pub writer_version: Option<WriterVersion>,
+    ///Bloom filters to write, keyed by column name. Applies to every leaf of nested columns.
+    pub bloom_filters: HashMap<String, BloomFilterOptions>,
+    ///Whether the column and offset page indexes are written. The column index needs page level statistics,
+    ///so enabling it raises statistics to EnabledStatistics::Page and disabling it lowers them to EnabledStatistics::Chunk.
+    pub page_index: Option<bool>,
+    ///Start a new file once the current one holds this many rows.
+    ///Rolling files are named <table>-00001.parquet, <table>-00002.parquet and so on, and listed in <table>.index.json.
+    pub max_rows_per_file: Option<usize>,
+    ///Start a new file once the current one reaches roughly this many bytes, counting buffered rows by their estimated encoded size.
+    pub max_bytes_per_file: Option<u64>,
+    ///Encrypt the files with Parquet modular encryption. Reading them back needs encryption::get_decryption_properties.
+    pub encryption: Option<EncryptionOptions>,
+}
+
+impl ParquetOptions {
+    pub fn get_writer_properties(&self, schema: &Schema) -> Result<WriterProperties> {
+        //Schema level metadata (such as GeoParquet's geo key) is only readable by non-Arrow tools as Parquet file metadata
+        let key_value_metadata = schema
+            .metadata()
+            .iter()
+            .map(|(key, value)| KeyValue::new(key.clone(), value.clone()))
+            .collect();
+        let mut builder = WriterProperties::builder().set_key_value_metadata(Some(key_value_metadata));
+        if let Some(compression) = self.compression {
+            builder = builder.set_compression(compression);
+        }
+        if let Some(max_row_group_size) = self.max_row_group_size {
+            builder = builder.set_max_row_group_size(max_row_group_size);
+        }
+        if let Some(data_page_size) = self.data_page_size {
+            builder = builder.set_data_page_size_limit(data_page_size);
+        }
+        if let Some(dictionary_enabled) = self.dictionary_enabled {
+            builder = builder.set_dictionary_enabled(dictionary_enabled);
+        }
+        let statistics = match (self.page_index, self.statistics) {
+            (Some(true), _) => Some(EnabledStatistics::Page),
+            (Some(false), None | Some(EnabledStatistics::Page)) => Some(EnabledStatistics::Chunk),
+            (_, statistics) => statistics,
+        };
+        if let Some(statistics) = statistics {
+            builder = builder.set_statistics_enabled(statistics);
+        }
+        if let Some(page_index) = self.page_index {
+            builder = builder.set_offset_index_disabled(!page_index);
+        }
+        if let Some(writer_version) = self.writer_version {
+            builder = builder.set_writer_version(writer_version);
+        }
+        if let Some(encryption) = &self.encryption {
+            builder = builder.with_file_encryption_properties(encryption.get_encryption_properties(schema)?);
+        }
+        if self.column_dictionary.is_empty() && self.bloom_filters.is_empty() {
+            return Ok(builder.build());
+        }
+
+        for name in self.column_dictionary.keys().chain(self.bloom_filters.keys()) {
+            if schema.field_with_name(name).is_err() {
+                bail!("Column {name} has Parquet options but is not in the schema.");
+            }
+        }
+        for (name, bloom_filter) in self.bloom_filters.iter() {
+            if let Some(fpp) = bloom_filter.fpp
+                && !(fpp > 0.0 && fpp < 1.0)
+            {
+                bail!("Bloom filter false positive probability of column {name} must be between 0 and 1, got {fpp}.");
+            }
+        }
+        //Parquet properties are set per leaf column, so nested Arrow columns are expanded to their leaves
+        let descriptor = ArrowSchemaConverter::new().convert(schema)?;
+        for column in descriptor.columns() {
+            let path = column.path();
+            let name = &path.parts()[0];
+            if let Some(enabled) = self.column_dictionary.get(name) {
+                builder = builder.set_column_dictionary_enabled(path.clone(), *enabled);
+            }
+            if let Some(bloom_filter) = self.bloom_filters.get(name) {
+                builder = builder.set_column_bloom_filter_enabled(path.clone(), true);
+                if let Some(ndv) = bloom_filter.ndv {
+                    builder = builder.set_column_bloom_filter_ndv(path.clone(), ndv);
+                }
+                if let Some(fpp) = bloom_filter.fpp {
+                    builder = builder.set_column_bloom_filter_fpp(path.clone(), fpp);
+                }
+            }
+        }
+        Ok(builder.build())
+    }
+}
+
+///One of the files written by a ParquetWriter.
+struct ParquetPart {
+    file_path: PathBuf,
+    temp_path: PathBuf,
+    rows: usize,
+    bytes: u64,
+}
+
 pub struct ParquetWriter {
     file_path: PathBuf,
     temp_path: PathBuf,
+    options: ParquetOptions,
+    properties: Option<WriterProperties>,
     writer: Option<ArrowWriter<File>>,
     schema: Option<Arc<Schema>>,
+    parts: Vec<ParquetPart>,
 }
 
 impl ParquetWriter {
-    pub fn new(file_path: PathBuf) -> ParquetWriter {
-        let mut temp_path = file_path.clone();
-        temp_path.set_extension("temp");
+    pub fn new(file_path: PathBuf, options: ParquetOptions) -> ParquetWriter {
+        let temp_path = append_extension(&file_path, "temp");
         ParquetWriter {
-            file_path: file_path,
-            temp_path: temp_path,
+            file_path,
+            temp_path,
+            options,
+            properties: None,
             writer: None,
             schema: None,
+            parts: Vec::new(),
         }
     }
+
+    fn is_rolling(&self) -> bool {
+        self.options.max_rows_per_file.is_some() || self.options.max_bytes_per_file.is_some()
+    }
+
+    ///Index listing the files of a rolling writer, written last so that its presence marks the table as complete.
+    fn get_index_path(&self) -> PathBuf {
+        self.file_path.with_extension("index.json")
+    }
+
+    fn get_part_path(&self, number: usize) -> PathBuf {
+        if !self.is_rolling() {
+            return self.file_path.clone();
+        }
+        let stem = self.file_path.file_stem().unwrap_or_default().to_string_lossy();
+        let mut path = self.file_path.with_file_name(format!("{stem}-{number:05}"));
+        if let Some(extension) = self.file_path.extension() {
+            path.set_extension(extension);
+        }
+        path
+    }
+
+    fn open_part(&mut self) -> Result<()> {
+        let Some(schema) = self.schema.clone() else {
+            bail!("No file handle.");
+        };
+        let file_path = self.get_part_path(self.parts.len() + 1);
+        let temp_path = if self.is_rolling() { append_extension(&file_path, "temp") } else { self.temp_path.clone() };
+        let file = File::create(&temp_path)?;
+        self.writer = Some(ArrowWriter::try_new(file, schema, self.properties.clone())?);
+        self.parts.push(ParquetPart {
+            file_path,
+            temp_path,
+            rows: 0,
+            bytes: 0,
+        });
+        Ok(())
+    }
+
+    fn close_part(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            writer.close()?;
+            if let Some(part) = self.parts.last_mut() {
+                part.bytes = fs::metadata(&part.temp_path)?.len();
+            }
+        }
+        Ok(())
+    }
+
+    ///Parts listed in the index of a previous backup of the table, if there is one.
+    fn get_previous_parts(&self) -> Result<Vec<PathBuf>> {
+        let text = match fs::read_to_string(self.get_index_path()) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => bail!(e),
+        };
+        let index: serde_json::Value = serde_json::from_str(&text)
+            .with_context(|| format!("Could not read the previous index {}", self.get_index_path().display()))?;
+        let files = index["files"].as_array().map(Vec::as_slice).unwrap_or_default();
+        //Only plain file names are accepted, so that an edited index cannot point outside the table's directory
+        Ok(files
+            .iter()
+            .filter_map(|file| file["path"].as_str())
+            .filter(|name| Path::new(name).file_name() == Some(OsStr::new(name)))
+            .map(|name| self.file_path.with_file_name(name))
+            .collect())
+    }
+
+    fn write_index(&self) -> Result<()> {
+        let files: Vec<serde_json::Value> = self
+            .parts
+            .iter()
+            .map(|part| {
+                serde_json::json!({
+                    "path": part.file_path.file_name().unwrap_or_default().to_string_lossy(),
+                    "rows": part.rows,
+                    "bytes": part.bytes,
+                })
+            })
+            .collect();
+        let index = serde_json::json!({
+            "rows": self.parts.iter().map(|part| part.rows).sum::<usize>(),
+            "files": files,
+        });
+        fs::write(&self.temp_path, serde_json::to_string_pretty(&index)?)?;
+        fs::rename(&self.temp_path, self.get_index_path())?;
+        Ok(())
+    }
 }
 
 impl DataWriter for ParquetWriter {
     fn setup(&mut self, schema: Arc<Schema>) -> Result<()> {
-        let file = File::create(&self.temp_path)?;
-        self.writer = Some(ArrowWriter::try_new(file, schema.clone(), None)?);
+        if self.options.max_rows_per_file == Some(0) || self.options.max_bytes_per_file == Some(0) {
+            bail!("Parquet files must be allowed to hold at least one row and byte.");
+        }
+        self.properties = Some(self.options.get_writer_properties(&schema)?);
         self.schema = Some(schema);
-        Ok(())
+        self.open_part()
     }
 
     fn write(&mut self, batch: &RecordBatch) -> Result<()> {
-        if let Some(ref mut writer) = self.writer {
-            writer.write(batch)?;
-        } else {
-            bail!("No file handle.")
+        //Batches are split across files so that no file holds more than max_rows_per_file rows
+        let mut offset = 0;
+        while offset < batch.num_rows() {
+            if self.writer.is_none() {
+                self.open_part()?;
+            }
+            let (Some(writer), Some(part)) = (self.writer.as_mut(), self.parts.last_mut()) else {
+                bail!("No file handle.");
+            };
+            let mut length = batch.num_rows() - offset;
+            if let Some(max_rows) = self.options.max_rows_per_file {
+                length = length.min(max_rows - part.rows);
+            }
+            writer.write(&batch.slice(offset, length))?;
+            part.rows += length;
+            offset += length;
+            let bytes = (writer.bytes_written() + writer.in_progress_size()) as u64;
+            if self.options.max_rows_per_file.is_some_and(|max_rows| part.rows >= max_rows)
+                || self.options.max_bytes_per_file.is_some_and(|max_bytes| bytes >= max_bytes)
+            {
+                self.close_part()?;
+            }
         }
         Ok(())
     }
 
     fn finish(&mut self) -> Result<()> {
-        if let Some(ref mut writer) = self.writer {
-            writer.finish()?;
-        } else {
+        if self.parts.is_empty() {
             bail!("Invalid Parquet writer.");
         }
-        fs::rename(&self.temp_path, &self.file_path)?;
+        self.close_part()?;
+        let previous_parts = self.get_previous_parts()?;
+        //The index marks a rolling table as complete, so it is removed before any part is replaced and written after all of
+        //them, so that it never lists a mix of old and new parts
+        remove_file(&self.get_index_path())?;
+        for part in self.parts.iter() {
+            fs::rename(&part.temp_path, &part.file_path)?;
+        }
+        if self.is_rolling() {
+            self.write_index()?;
+            remove_file(&self.file_path)?;
+        }
+        for path in previous_parts {
+            if !self.parts.iter().any(|part| part.file_path == path) {
+                remove_file(&path)?;
+            }
+        }
         Ok(())
     }
 
     fn abort(&mut self) -> Result<()> {
-        if let Err(e) = fs::remove_file(&self.temp_path) {
-            if e.kind() != ErrorKind::NotFound {
-                bail!(e);
-            }
+        self.writer = None;
+        for part in self.parts.iter() {
+            remove_file(&part.temp_path)?;
+            remove_file(&part.file_path)?;
         }
-        if let Err(e) = fs::remove_file(&self.file_path) {
-            if e.kind() != ErrorKind::NotFound {
-                bail!(e);
-            }
+        for path in self.get_previous_parts()? {
+            remove_file(&path)?;
         }
-        Ok(())
+        remove_file(&self.temp_path)?;
+        remove_file(&self.file_path)?;
+        remove_file(&self.get_index_path())
     }
 }
 
 pub struct ParquetWriterFactory {
     file_path: PathBuf,
+    options: ParquetOptions,
 }
 
 impl ParquetWriterFactory {
     pub fn new(file_path: PathBuf) -> Self {
-        Self { file_path }
+        Self {
+            file_path,
+            options: ParquetOptions::default(),
+        }
+    }
+
+    pub fn with_options(mut self, options: ParquetOptions) -> Self {
+        self.options = options;
+        self
     }
 }
 
 impl DataWriterFactory for ParquetWriterFactory {
     fn create(&self) -> Box<dyn DataWriter> {
-        Box::new(ParquetWriter::new(self.file_path.clone()))
+        Box::new(ParquetWriter::new(self.file_path.clone(), self.options.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writers::test_util::{batch, write};
+    use parquet::basic::{Encoding, ZstdLevel};
+    use parquet::file::properties::ReaderProperties;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::file::serialized_reader::ReadOptionsBuilder;
+
+    #[test]
+    fn writes_configured_properties() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("table.parquet");
+        let options = ParquetOptions {
+            compression: Some(Compression::ZSTD(ZstdLevel::try_new(3).unwrap())),
+            max_row_group_size: Some(10),
+            statistics: Some(EnabledStatistics::None),
+            writer_version: Some(WriterVersion::PARQUET_2_0),
+            ..Default::default()
+        };
+        let factory = ParquetWriterFactory::new(path.clone()).with_options(options);
+        write(&factory, batch(0).schema(), &[batch(25)]).unwrap();
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().version(), 2);
+        assert_eq!(metadata.num_row_groups(), 3);
+        let column = metadata.row_group(0).column(0);
+        assert_eq!(column.compression(), Compression::ZSTD(ZstdLevel::default()));
+        assert!(column.statistics().is_none());
+        assert!(!directory.path().join("table.parquet.temp").exists());
+    }
+
+    #[test]
+    fn per_column_dictionary() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("table.parquet");
+        let options = ParquetOptions {
+            dictionary_enabled: Some(false),
+            column_dictionary: HashMap::from([(String::from("name"), true)]),
+            ..Default::default()
+        };
+        let factory = ParquetWriterFactory::new(path.clone()).with_options(options);
+        write(&factory, batch(0).schema(), &[batch(25)]).unwrap();
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        let row_group = reader.metadata().row_group(0);
+        assert!(!row_group.column(0).encodings().contains(&Encoding::RLE_DICTIONARY));
+        assert!(row_group.column(1).encodings().contains(&Encoding::RLE_DICTIONARY));
+    }
+
+    #[test]
+    fn unknown_dictionary_column() {
+        let directory = tempfile::tempdir().unwrap();
+        let options = ParquetOptions {
+            column_dictionary: HashMap::from([(String::from("missing"), true)]),
+            ..Default::default()
+        };
+        let factory = ParquetWriterFactory::new(directory.path().join("table.parquet")).with_options(options);
+        assert!(write(&factory, batch(0).schema(), &[batch(1)]).is_err());
+    }
+
+    #[test]
+    fn bloom_filters() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("table.parquet");
+        let options = ParquetOptions {
+            bloom_filters: HashMap::from([(String::from("name"), BloomFilterOptions { ndv: Some(100), fpp: Some(0.01) })]),
+            ..Default::default()
+        };
+        let factory = ParquetWriterFactory::new(path.clone()).with_options(options);
+        write(&factory, batch(0).schema(), &[batch(25)]).unwrap();
+
+        let read_options = ReadOptionsBuilder::new()
+            .with_reader_properties(ReaderProperties::builder().set_read_bloom_filter(true).build())
+            .build();
+        let reader = SerializedFileReader::new_with_options(File::open(&path).unwrap(), read_options).unwrap();
+        let row_group = reader.get_row_group(0).unwrap();
+        assert!(row_group.get_column_bloom_filter(0).is_none());
+        let bloom_filter = row_group.get_column_bloom_filter(1).unwrap();
+        assert!(bloom_filter.check(&"name 1"));
+        assert!(!bloom_filter.check(&"someone else"));
+    }
+
+    #[test]
+    fn invalid_bloom_filter_fpp() {
+        let directory = tempfile::tempdir().unwrap();
+        let options = ParquetOptions {
+            bloom_filters: HashMap::from([(String::from("id"), BloomFilterOptions { ndv: None, fpp: Some(1.5) })]),
+            ..Default::default()
+        };
+        let factory = ParquetWriterFactory::new(directory.path().join("table.parquet")).with_options(options);
+        assert!(write(&factory, batch(0).schema(), &[batch(1)]).is_err());
+    }
+
+    #[test]
+    fn page_index() {
+        let directory = tempfile::tempdir().unwrap();
+        for enabled in [true, false] {
+            let path = directory.path().join(format!("{enabled}.parquet"));
+            let options = ParquetOptions {
+                statistics: Some(EnabledStatistics::Chunk),
+                page_index: Some(enabled),
+                ..Default::default()
+            };
+            let factory = ParquetWriterFactory::new(path.clone()).with_options(options);
+            write(&factory, batch(0).schema(), &[batch(25)]).unwrap();
+
+            let read_options = ReadOptionsBuilder::new().with_page_index().build();
+            let reader = SerializedFileReader::new_with_options(File::open(&path).unwrap(), read_options).unwrap();
+            let metadata = reader.metadata();
+            assert_eq!(metadata.row_group(0).column(0).column_index_offset().is_some(), enabled);
+            assert_eq!(metadata.row_group(0).column(0).offset_index_offset().is_some(), enabled);
+            assert_eq!(metadata.offset_index().is_some_and(|index| !index[0].is_empty()), enabled);
+        }
+    }
+
+    fn read_index(path: &std::path::Path) -> serde_json::Value {
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn rolls_by_row_count() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("ledger.parquet");
+        let options = ParquetOptions { max_rows_per_file: Some(10), ..Default::default() };
+        let mut writer = ParquetWriterFactory::new(path.clone()).with_options(options).create();
+        writer.setup(batch(1).schema()).unwrap();
+        writer.write(&batch(7)).unwrap();
+        writer.write(&batch(18)).unwrap();
+        writer.finish().unwrap();
+
+        let index = read_index(&directory.path().join("ledger.index.json"));
+        assert_eq!(index["rows"], 25);
+        let files = index["files"].as_array().unwrap();
+        let names: Vec<&str> = files.iter().map(|file| file["path"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["ledger-00001.parquet", "ledger-00002.parquet", "ledger-00003.parquet"]);
+        for (file, rows) in files.iter().zip([10, 10, 5]) {
+            assert_eq!(file["rows"], rows);
+            let part = directory.path().join(file["path"].as_str().unwrap());
+            assert_eq!(file["bytes"], fs::metadata(&part).unwrap().len());
+            let reader = SerializedFileReader::new(File::open(&part).unwrap()).unwrap();
+            assert_eq!(reader.metadata().file_metadata().num_rows(), rows);
+        }
+        let mut entries: Vec<String> = fs::read_dir(directory.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        entries.sort();
+        assert_eq!(entries, vec!["ledger-00001.parquet", "ledger-00002.parquet", "ledger-00003.parquet", "ledger.index.json"]);
+    }
+
+    #[test]
+    fn rolls_by_bytes() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("ledger.parquet");
+        let options = ParquetOptions { max_bytes_per_file: Some(1), ..Default::default() };
+        let mut writer = ParquetWriterFactory::new(path.clone()).with_options(options).create();
+        writer.setup(batch(1).schema()).unwrap();
+        for _ in 0..3 {
+            writer.write(&batch(5)).unwrap();
+        }
+        writer.finish().unwrap();
+        let index = read_index(&directory.path().join("ledger.index.json"));
+        assert_eq!(index["files"].as_array().unwrap().len(), 3);
+        assert_eq!(index["rows"], 15);
+    }
+
+    #[test]
+    fn rolling_without_rows() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("ledger.parquet");
+        let options = ParquetOptions { max_rows_per_file: Some(10), ..Default::default() };
+        let mut writer = ParquetWriterFactory::new(path.clone()).with_options(options).create();
+        writer.setup(batch(1).schema()).unwrap();
+        writer.finish().unwrap();
+        let index = read_index(&directory.path().join("ledger.index.json"));
+        assert_eq!(index["files"].as_array().unwrap().len(), 1);
+        assert!(directory.path().join("ledger-00001.parquet").exists());
+    }
+
+    #[test]
+    fn rolling_abort_removes_parts() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("ledger.parquet");
+        let options = ParquetOptions { max_rows_per_file: Some(10), ..Default::default() };
+        let mut writer = ParquetWriterFactory::new(path.clone()).with_options(options).create();
+        writer.setup(batch(1).schema()).unwrap();
+        writer.write(&batch(25)).unwrap();
+        writer.abort().unwrap();
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn replaces_previous_parts() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("ledger.parquet");
+        let entries = || {
+            let mut entries: Vec<String> = fs::read_dir(directory.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+                .collect();
+            entries.sort();
+            entries
+        };
+        write(&ParquetWriterFactory::new(path.clone()), batch(0).schema(), &[batch(5)]).unwrap();
+        let rolling = ParquetOptions { max_rows_per_file: Some(10), ..Default::default() };
+        let factory = ParquetWriterFactory::new(path.clone()).with_options(rolling);
+        write(&factory, batch(0).schema(), &[batch(45)]).unwrap();
+        assert_eq!(entries().len(), 6);
+        assert!(!path.exists());
+
+        write(&factory, batch(0).schema(), &[batch(15)]).unwrap();
+        assert_eq!(entries(), vec!["ledger-00001.parquet", "ledger-00002.parquet", "ledger.index.json"]);
+        assert_eq!(read_index(&directory.path().join("ledger.index.json"))["rows"], 15);
+
+        write(&ParquetWriterFactory::new(path.clone()), batch(0).schema(), &[batch(5)]).unwrap();
+        assert_eq!(entries(), vec!["ledger.parquet"]);
     }
 }
@@ -0,0 +1,510 @@
+use crate::writers::{DataWriter, DataWriterFactory, append_extension};
+use anyhow::{Result, bail};
+use arrow::array::{AsArray, UInt32Array};
+use arrow::compute::{cast, take_record_batch};
+use arrow::datatypes::{DataType, Date32Type};
+use arrow::{array::RecordBatch, datatypes::Schema};
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+//Directory value Hive uses for rows whose partition value is null
+const NULL_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+const DEFAULT_MAX_OPEN_FILES: usize = 64;
+
+///Creates the factory of the writer for a single partition file.
+pub type PartitionWriterFactory = dyn Fn(PathBuf) -> Box<dyn DataWriterFactory> + Send + Sync;
+
+///How the value of a column is turned into a partition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartitionTransform {
+    ///The value itself.
+    Identity,
+    ///The year of a date or timestamp, such as 2024.
+    Year,
+    ///The month of a date or timestamp, such as 2024-05.
+    Month,
+    ///The day of a date or timestamp, such as 2024-05-17.
+    Day,
+    ///One of this many buckets, chosen by a stable hash of the value.
+    Bucket(u32),
+}
+
+///A directory level of a partitioned backup, written as name=value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionExpression {
+    pub name: String,
+    pub column: String,
+    pub transform: PartitionTransform,
+}
+
+impl PartitionExpression {
+    pub fn value(column: &str) -> PartitionExpression {
+        PartitionExpression::new(column, column, PartitionTransform::Identity)
+    }
+
+    pub fn year(column: &str) -> PartitionExpression {
+        PartitionExpression::new(&format!("{column}_year"), column, PartitionTransform::Year)
+    }
+
+    pub fn month(column: &str) -> PartitionExpression {
+        PartitionExpression::new(&format!("{column}_month"), column, PartitionTransform::Month)
+    }
+
+    pub fn day(column: &str) -> PartitionExpression {
+        PartitionExpression::new(&format!("{column}_day"), column, PartitionTransform::Day)
+    }
+
+    pub fn bucket(column: &str, buckets: u32) -> PartitionExpression {
+        PartitionExpression::new(&format!("{column}_bucket"), column, PartitionTransform::Bucket(buckets))
+    }
+
+    fn new(name: &str, column: &str, transform: PartitionTransform) -> PartitionExpression {
+        PartitionExpression {
+            name: name.to_string(),
+            column: column.to_string(),
+            transform,
+        }
+    }
+
+    ///Replaces the name of the partition directories, such as year instead of created_at_year.
+    pub fn with_name(mut self, name: &str) -> PartitionExpression {
+        self.name = name.to_string();
+        self
+    }
+
+    fn validate(&self, schema: &Schema) -> Result<()> {
+        let Ok(field) = schema.field_with_name(&self.column) else {
+            bail!("Partition column {} is not in the schema.", self.column);
+        };
+        match self.transform {
+            PartitionTransform::Year | PartitionTransform::Month | PartitionTransform::Day
+                if !matches!(field.data_type(), DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, _)) =>
+            {
+                bail!("Partition column {} must be a date or timestamp to be truncated.", self.column);
+            }
+            PartitionTransform::Bucket(0) => bail!("Partition column {} must have at least one bucket.", self.column),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    ///Gets the escaped partition value of every row in the batch.
+    fn get_values(&self, batch: &RecordBatch) -> Result<Vec<String>> {
+        let Some(column) = batch.column_by_name(&self.column) else {
+            bail!("Partition column {} is not in the batch.", self.column);
+        };
+        let format = match self.transform {
+            PartitionTransform::Identity | PartitionTransform::Bucket(_) => {
+                let values = cast(column, &DataType::Utf8)?;
+                let values = values.as_string::<i32>();
+                return Ok(values
+                    .iter()
+                    .map(|value| match (value, self.transform) {
+                        (None, _) => NULL_PARTITION.to_string(),
+                        (Some(value), PartitionTransform::Bucket(buckets)) => (hash(value.as_bytes()) % buckets as u64).to_string(),
+                        (Some(value), _) => escape(value),
+                    })
+                    .collect());
+            }
+            PartitionTransform::Year => "%Y",
+            PartitionTransform::Month => "%Y-%m",
+            PartitionTransform::Day => "%Y-%m-%d",
+        };
+        //Timestamps with a time zone are cast to the date in that zone
+        let dates = cast(column, &DataType::Date32)?;
+        Ok(dates
+            .as_primitive::<Date32Type>()
+            .iter()
+            .map(|date| match date {
+                Some(date) => Date32Type::to_naive_date(date).format(format).to_string(),
+                None => NULL_PARTITION.to_string(),
+            })
+            .collect())
+    }
+}
+
+///64 bit FNV-1a, which unlike std's hasher is stable across releases so rows keep their bucket between backups.
+fn hash(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+///Percent encodes the characters Hive escapes in partition directory names.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for character in value.chars() {
+        if character.is_control() || "\"#%'*/:=?\\{[]^".contains(character) {
+            escaped.push_str(&format!("%{:02X}", character as u32));
+        } else {
+            escaped.push(character);
+        }
+    }
+    escaped
+}
+
+fn remove_directory(path: &Path) -> Result<()> {
+    if let Err(e) = fs::remove_dir_all(path)
+        && e.kind() != ErrorKind::NotFound
+    {
+        bail!(e);
+    }
+    Ok(())
+}
+
+struct OpenPartition {
+    writer: Box<dyn DataWriter>,
+    last_used: u64,
+}
+
+///Splits batches into Hive style partition directories, such as accounts/currency_code=USD/part-0.parquet.
+///Every partition is written into a temporary directory which replaces the backup's directory once the whole table is written.
+///Partition columns are kept in the files.
+pub struct PartitionedWriter {
+    directory: PathBuf,
+    temp_directory: PathBuf,
+    extension: String,
+    partitions: Vec<PartitionExpression>,
+    max_open_files: usize,
+    factory: Arc<PartitionWriterFactory>,
+    schema: Option<Arc<Schema>>,
+    open: HashMap<String, OpenPartition>,
+    //Number of files written for each partition so far, a partition whose writer was closed to stay under max_open_files continues in a new file
+    files: HashMap<String, usize>,
+    writes: u64,
+}
+
+impl PartitionedWriter {
+    fn get_writer(&mut self, partition: &str) -> Result<&mut Box<dyn DataWriter>> {
+        self.writes += 1;
+        if !self.open.contains_key(partition) {
+            if self.open.len() >= self.max_open_files {
+                self.close_least_recently_used()?;
+            }
+            let Some(schema) = self.schema.clone() else {
+                bail!("No file handle.");
+            };
+            let file_number = self.files.entry(partition.to_string()).or_insert(0);
+            let mut path = self.temp_directory.join(partition);
+            fs::create_dir_all(&path)?;
+            path.push(format!("part-{}.{}", file_number, self.extension));
+            *file_number += 1;
+            let mut writer = (self.factory)(path).create();
+            writer.setup(schema)?;
+            self.open.insert(partition.to_string(), OpenPartition { writer, last_used: 0 });
+        }
+        let Some(open) = self.open.get_mut(partition) else {
+            bail!("No file handle.");
+        };
+        open.last_used = self.writes;
+        Ok(&mut open.writer)
+    }
+
+    fn close_least_recently_used(&mut self) -> Result<()> {
+        let partition = self
+            .open
+            .iter()
+            .min_by_key(|(_, open)| open.last_used)
+            .map(|(partition, _)| partition.clone());
+        if let Some(partition) = partition
+            && let Some(mut open) = self.open.remove(&partition)
+        {
+            open.writer.finish()?;
+        }
+        Ok(())
+    }
+}
+
+impl DataWriter for PartitionedWriter {
+    fn setup(&mut self, schema: Arc<Schema>) -> Result<()> {
+        if self.partitions.is_empty() {
+            bail!("A partitioned backup needs at least one partition expression.");
+        }
+        if self.max_open_files == 0 {
+            bail!("A partitioned backup must be allowed at least one open file.");
+        }
+        for partition in self.partitions.iter() {
+            partition.validate(&schema)?;
+        }
+        //Leftovers of an earlier failed backup would otherwise be committed along with this one
+        remove_directory(&self.temp_directory)?;
+        fs::create_dir_all(&self.temp_directory)?;
+        self.schema = Some(schema);
+        Ok(())
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        let values = self
+            .partitions
+            .iter()
+            .map(|partition| partition.get_values(batch))
+            .collect::<Result<Vec<_>>>()?;
+        //Rows are grouped by partition in the order each partition is first seen
+        let mut groups: Vec<(String, Vec<u32>)> = Vec::new();
+        let mut group_indices: HashMap<String, usize> = HashMap::new();
+        for row in 0..batch.num_rows() {
+            let partition = self
+                .partitions
+                .iter()
+                .zip(values.iter())
+                .map(|(partition, values)| format!("{}={}", escape(&partition.name), values[row]))
+                .collect::<Vec<_>>()
+                .join("/");
+            let index = *group_indices.entry(partition.clone()).or_insert_with(|| {
+                groups.push((partition, Vec::new()));
+                groups.len() - 1
+            });
+            groups[index].1.push(row as u32);
+        }
+        for (partition, rows) in groups {
+            let rows = take_record_batch(batch, &UInt32Array::from(rows))?;
+            self.get_writer(&partition)?.write(&rows)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if self.schema.is_none() {
+            bail!("Invalid partitioned writer.");
+        }
+        for (_, mut open) in self.open.drain() {
+            open.writer.finish()?;
+        }
+        //The previous backup is moved aside rather than deleted first, so that a failed rename does not lose it
+        let previous = append_extension(&self.directory, "old");
+        remove_directory(&previous)?;
+        if self.directory.exists() {
+            fs::rename(&self.directory, &previous)?;
+        }
+        fs::rename(&self.temp_directory, &self.directory)?;
+        remove_directory(&previous)?;
+        Ok(())
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        for (_, mut open) in self.open.drain() {
+            open.writer.abort()?;
+        }
+        remove_directory(&self.temp_directory)?;
+        remove_directory(&self.directory)?;
+        Ok(())
+    }
+}
+
+pub struct PartitionedWriterFactory {
+    directory: PathBuf,
+    extension: String,
+    partitions: Vec<PartitionExpression>,
+    max_open_files: usize,
+    factory: Arc<PartitionWriterFactory>,
+}
+
+impl PartitionedWriterFactory {
+    ///Creates a factory writing partitions below directory. Each partition file is written by the factory returned by factory for
+    ///its path, which is named part-<n>.<extension>.
+    pub fn new(
+        directory: PathBuf,
+        extension: &str,
+        partitions: Vec<PartitionExpression>,
+        factory: impl Fn(PathBuf) -> Box<dyn DataWriterFactory> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            directory,
+            extension: extension.to_string(),
+            partitions,
+            max_open_files: DEFAULT_MAX_OPEN_FILES,
+            factory: Arc::new(factory),
+        }
+    }
+
+    ///Sets how many partition files can be open at once. Past this, the least recently written partition file is finished. Defaults to 64.
+    pub fn with_max_open_files(mut self, max_open_files: usize) -> Self {
+        self.max_open_files = max_open_files;
+        self
+    }
+}
+
+impl DataWriterFactory for PartitionedWriterFactory {
+    fn create(&self) -> Box<dyn DataWriter> {
+        Box::new(PartitionedWriter {
+            directory: self.directory.clone(),
+            temp_directory: append_extension(&self.directory, "temp"),
+            extension: self.extension.clone(),
+            partitions: self.partitions.clone(),
+            max_open_files: self.max_open_files,
+            factory: self.factory.clone(),
+            schema: None,
+            open: HashMap::new(),
+            files: HashMap::new(),
+            writes: 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writers::CsvWriterFactory;
+    use crate::writers::test_util::write;
+    use arrow::array::{Int64Array, StringArray, TimestampMicrosecondArray};
+    use arrow::datatypes::{Field, TimeUnit};
+
+    fn ledger() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("currency_code", DataType::Utf8, true),
+            Field::new("created_at", DataType::Timestamp(TimeUnit::Microsecond, None), true),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+                Arc::new(StringArray::from(vec![Some("USD"), Some("EUR"), Some("USD"), None])),
+                Arc::new(TimestampMicrosecondArray::from(vec![
+                    Some(1_704_067_200_000_000),
+                    Some(1_709_251_200_000_000),
+                    Some(1_709_337_600_000_000),
+                    None,
+                ])),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn factory(directory: PathBuf, partitions: Vec<PartitionExpression>) -> PartitionedWriterFactory {
+        PartitionedWriterFactory::new(directory, "csv", partitions, |path| Box::new(CsvWriterFactory::new(path)))
+    }
+
+    fn ids(path: &Path) -> Vec<String> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').next().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn partitions_by_value() {
+        let directory = tempfile::tempdir().unwrap();
+        let accounts = directory.path().join("accounts");
+        let factory = factory(accounts.clone(), vec![PartitionExpression::value("currency_code")]);
+        write(&factory, ledger().schema(), &[ledger()]).unwrap();
+        assert_eq!(ids(&accounts.join("currency_code=USD/part-0.csv")), vec!["1", "3"]);
+        assert_eq!(ids(&accounts.join("currency_code=EUR/part-0.csv")), vec!["2"]);
+        assert_eq!(ids(&accounts.join("currency_code=__HIVE_DEFAULT_PARTITION__/part-0.csv")), vec!["4"]);
+        assert!(!directory.path().join("accounts.temp").exists());
+    }
+
+    #[test]
+    fn partitions_by_year_and_month() {
+        let directory = tempfile::tempdir().unwrap();
+        let transactions = directory.path().join("transactions");
+        let partitions = vec![
+            PartitionExpression::year("created_at").with_name("year"),
+            PartitionExpression::month("created_at").with_name("month"),
+        ];
+        let factory = factory(transactions.clone(), partitions);
+        write(&factory, ledger().schema(), &[ledger(), ledger()]).unwrap();
+        assert_eq!(ids(&transactions.join("year=2024/month=2024-01/part-0.csv")), vec!["1", "1"]);
+        assert_eq!(ids(&transactions.join("year=2024/month=2024-03/part-0.csv")), vec!["2", "3", "2", "3"]);
+        assert!(transactions.join("year=__HIVE_DEFAULT_PARTITION__/month=__HIVE_DEFAULT_PARTITION__").exists());
+    }
+
+    #[test]
+    fn partitions_by_bucket() {
+        let directory = tempfile::tempdir().unwrap();
+        let accounts = directory.path().join("accounts");
+        let factory = factory(accounts.clone(), vec![PartitionExpression::bucket("id", 2)]);
+        write(&factory, ledger().schema(), &[ledger()]).unwrap();
+        let mut rows: Vec<String> = (0..2)
+            .flat_map(|bucket| {
+                let path = accounts.join(format!("id_bucket={bucket}/part-0.csv"));
+                if path.exists() { ids(&path) } else { Vec::new() }
+            })
+            .collect();
+        rows.sort();
+        assert_eq!(rows, vec!["1", "2", "3", "4"]);
+        assert_eq!(hash(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn escapes_values() {
+        assert_eq!(escape("a/b=c%"), "a%2Fb%3Dc%25");
+        assert_eq!(escape("plain value"), "plain value");
+    }
+
+    #[test]
+    fn caps_open_files() {
+        let directory = tempfile::tempdir().unwrap();
+        let accounts = directory.path().join("accounts");
+        let factory = factory(accounts.clone(), vec![PartitionExpression::value("currency_code")]).with_max_open_files(1);
+        write(&factory, ledger().schema(), &[ledger(), ledger()]).unwrap();
+        assert_eq!(ids(&accounts.join("currency_code=USD/part-0.csv")), vec!["1", "3"]);
+        assert_eq!(ids(&accounts.join("currency_code=USD/part-1.csv")), vec!["1", "3"]);
+        assert!(!accounts.join("currency_code=USD/part-2.csv").exists());
+    }
+
+    #[test]
+    fn replaces_previous_backup() {
+        let directory = tempfile::tempdir().unwrap();
+        let accounts = directory.path().join("accounts");
+        fs::create_dir_all(accounts.join("currency_code=GBP")).unwrap();
+        let factory = factory(accounts.clone(), vec![PartitionExpression::value("currency_code")]);
+        write(&factory, ledger().schema(), &[ledger()]).unwrap();
+        assert!(!accounts.join("currency_code=GBP").exists());
+        assert!(accounts.join("currency_code=USD").exists());
+        assert!(!directory.path().join("accounts.old").exists());
+    }
+
+    #[test]
+    fn names_with_dots() {
+        let directory = tempfile::tempdir().unwrap();
+        let partitions = vec![PartitionExpression::value("currency_code")];
+        let mut writers: Vec<Box<dyn DataWriter>> = ["db.accounts", "db.transactions"]
+            .into_iter()
+            .map(|name| factory(directory.path().join(name), partitions.clone()).create())
+            .collect();
+        for writer in writers.iter_mut() {
+            writer.setup(ledger().schema()).unwrap();
+        }
+        writers[0].write(&ledger().slice(0, 1)).unwrap();
+        writers[1].write(&ledger().slice(1, 1)).unwrap();
+        for writer in writers.iter_mut() {
+            writer.finish().unwrap();
+        }
+        assert_eq!(ids(&directory.path().join("db.accounts/currency_code=USD/part-0.csv")), vec!["1"]);
+        assert!(!directory.path().join("db.accounts/currency_code=EUR").exists());
+        assert_eq!(ids(&directory.path().join("db.transactions/currency_code=EUR/part-0.csv")), vec!["2"]);
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn abort_removes_partitions() {
+        let directory = tempfile::tempdir().unwrap();
+        let accounts = directory.path().join("accounts");
+        let mut writer = factory(accounts.clone(), vec![PartitionExpression::value("currency_code")]).create();
+        writer.setup(ledger().schema()).unwrap();
+        writer.write(&ledger()).unwrap();
+        writer.abort().unwrap();
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn invalid_partitions() {
+        let directory = tempfile::tempdir().unwrap();
+        let accounts = directory.path().join("accounts");
+        let invalid = [
+            vec![],
+            vec![PartitionExpression::value("missing")],
+            vec![PartitionExpression::year("currency_code")],
+            vec![PartitionExpression::bucket("id", 0)],
+        ];
+        for partitions in invalid {
+            assert!(write(&factory(accounts.clone(), partitions), ledger().schema(), &[]).is_err());
+        }
+    }
+}
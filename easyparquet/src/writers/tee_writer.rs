@@ -0,0 +1,191 @@
+use crate::writers::{DataWriter, DataWriterFactory};
+use anyhow::{Error, Result, bail};
+use arrow::{array::RecordBatch, datatypes::Schema};
+use std::sync::Arc;
+
+///Writes the same data to several outputs, so a table only needs to be read once to back it up in several formats.
+///Either every output is committed or every output is aborted: when any of them fails, including while finishing, all of them
+///are aborted, which also removes the outputs that were already committed.
+pub struct TeeWriter {
+    writers: Vec<Box<dyn DataWriter>>,
+}
+
+impl TeeWriter {
+    pub fn new(writers: Vec<Box<dyn DataWriter>>) -> TeeWriter {
+        TeeWriter { writers }
+    }
+
+    //Aborts every output after one has failed, returning the error of the failed output
+    fn abort_all(&mut self, error: Error) -> Error {
+        match self.abort() {
+            Ok(()) => error,
+            Err(abort_error) => error.context(format!("Failed to abort the other outputs: {abort_error}")),
+        }
+    }
+}
+
+impl DataWriter for TeeWriter {
+    fn setup(&mut self, schema: Arc<Schema>) -> Result<()> {
+        if self.writers.is_empty() {
+            bail!("Tee writer has no outputs.");
+        }
+        for i in 0..self.writers.len() {
+            if let Err(e) = self.writers[i].setup(schema.clone()) {
+                return Err(self.abort_all(e));
+            }
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        for i in 0..self.writers.len() {
+            if let Err(e) = self.writers[i].write(batch) {
+                return Err(self.abort_all(e));
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        for i in 0..self.writers.len() {
+            if let Err(e) = self.writers[i].finish() {
+                return Err(self.abort_all(e));
+            }
+        }
+        Ok(())
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        //Every output is aborted even if one fails to, the first failure is reported
+        let mut result = Ok(());
+        for writer in self.writers.iter_mut() {
+            if let Err(e) = writer.abort()
+                && result.is_ok()
+            {
+                result = Err(e);
+            }
+        }
+        result
+    }
+}
+
+pub struct TeeWriterFactory {
+    factories: Vec<Box<dyn DataWriterFactory>>,
+}
+
+impl TeeWriterFactory {
+    pub fn new(factories: Vec<Box<dyn DataWriterFactory>>) -> Self {
+        Self { factories }
+    }
+}
+
+impl DataWriterFactory for TeeWriterFactory {
+    fn create(&self) -> Box<dyn DataWriter> {
+        Box::new(TeeWriter::new(self.factories.iter().map(|factory| factory.create()).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writers::test_util::{batch, write};
+    use crate::writers::{CsvWriterFactory, ParquetWriterFactory};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::fs::{self, File};
+    use std::path::Path;
+
+    //Writer failing at the given stage, to check that the outputs written alongside it are aborted
+    #[derive(Clone, Copy, PartialEq)]
+    enum Stage {
+        Setup,
+        Write,
+        Finish,
+    }
+
+    struct FailingWriter {
+        stage: Stage,
+    }
+
+    impl DataWriter for FailingWriter {
+        fn setup(&mut self, _schema: Arc<Schema>) -> Result<()> {
+            if self.stage == Stage::Setup {
+                bail!("Setup failed.");
+            }
+            Ok(())
+        }
+
+        fn write(&mut self, _batch: &RecordBatch) -> Result<()> {
+            if self.stage == Stage::Write {
+                bail!("Write failed.");
+            }
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<()> {
+            bail!("Finish failed.");
+        }
+
+        fn abort(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct FailingWriterFactory {
+        stage: Stage,
+    }
+
+    impl DataWriterFactory for FailingWriterFactory {
+        fn create(&self) -> Box<dyn DataWriter> {
+            Box::new(FailingWriter { stage: self.stage })
+        }
+    }
+
+    fn factory(directory: &Path, failing: Option<Stage>) -> TeeWriterFactory {
+        let mut factories: Vec<Box<dyn DataWriterFactory>> = vec![
+            Box::new(ParquetWriterFactory::new(directory.join("users.parquet"))),
+            Box::new(CsvWriterFactory::new(directory.join("users.csv"))),
+        ];
+        if let Some(stage) = failing {
+            factories.push(Box::new(FailingWriterFactory { stage }));
+        }
+        TeeWriterFactory::new(factories)
+    }
+
+    #[test]
+    fn writes_every_output() {
+        //The outputs share the users stem, so their staging files must not collide
+        let directory = tempfile::tempdir().unwrap();
+        write(&factory(directory.path(), None), batch(2).schema(), &[batch(2)]).unwrap();
+        let mut reader = ParquetRecordBatchReaderBuilder::try_new(File::open(directory.path().join("users.parquet")).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(reader.next().unwrap().unwrap(), batch(2));
+        assert_eq!(fs::read_to_string(directory.path().join("users.csv")).unwrap(), "id,name\n0,name 0\n1,name 1\n");
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn failure_aborts_every_output() {
+        for stage in [Stage::Setup, Stage::Write, Stage::Finish] {
+            let directory = tempfile::tempdir().unwrap();
+            assert!(write(&factory(directory.path(), Some(stage)), batch(2).schema(), &[batch(2)]).is_err());
+            assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 0);
+        }
+    }
+
+    #[test]
+    fn abort_removes_every_output() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut writer = factory(directory.path(), None).create();
+        writer.setup(batch(2).schema()).unwrap();
+        writer.write(&batch(2)).unwrap();
+        writer.abort().unwrap();
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn no_outputs() {
+        assert!(write(&TeeWriterFactory::new(Vec::new()), batch(2).schema(), &[batch(2)]).is_err());
+    }
+}
@@ -0,0 +1,25 @@
+use crate::writers::DataWriterFactory;
+use anyhow::Result;
+use arrow::array::{Int64Array, RecordBatch, StringArray};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use std::sync::Arc;
+
+///Batch of ids counting up from 0, each with a distinct name.
+pub(crate) fn batch(rows: i64) -> RecordBatch {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("name", DataType::Utf8, false),
+    ]));
+    let names = StringArray::from_iter_values((0..rows).map(|i| format!("name {i}")));
+    RecordBatch::try_new(schema, vec![Arc::new(Int64Array::from_iter_values(0..rows)), Arc::new(names)]).unwrap()
+}
+
+///Writes the batches with a writer created by the factory and finishes it.
+pub(crate) fn write(factory: &dyn DataWriterFactory, schema: SchemaRef, batches: &[RecordBatch]) -> Result<()> {
+    let mut writer = factory.create();
+    writer.setup(schema)?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.finish()
+}
@@ -1,15 +1,29 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::Result;
 use crate::Config;
 use easyparquet::backups::BatchBackup;
+use easyparquet::encryption::{EncryptionOptions, LocalKeyFile};
+use easyparquet::writers::{OutputFormat, ParquetOptions};
 use mysql_async::Pool;
 
+//Columns of the users table holding personal information, encrypted with the pii key
+const PII_COLUMNS: [&str; 6] = ["first_name", "last_name", "email", "address", "phone_number", "date_of_birth"];
+
 pub async fn backup(config: &Config) -> Result<()> {
     let pool = Pool::new(config.get_uri().as_str());
     let root_directory = PathBuf::from(&config.backup_directory);
     let mut backup = BatchBackup::new(root_directory);
-    backup.add_table("users".to_string());
+    let users = backup.add_table("users".to_string());
+    if let Some(ref key_file) = config.encryption_key_file {
+        let keys = Arc::new(LocalKeyFile::open(Path::new(key_file))?);
+        let mut encryption = EncryptionOptions::new(keys, "footer");
+        for column in PII_COLUMNS {
+            encryption = encryption.with_column_key(column, "pii");
+        }
+        users.output_format = Some(OutputFormat::Parquet(ParquetOptions { encryption: Some(encryption), ..Default::default() }));
+    }
         backup.add_table("accounts".to_string());
     backup.execute(pool).await?;
     Ok(())
@@ -1,9 +1,8 @@
-use dotenvy;
-
 #[derive(Debug)]
 pub struct Config {
     pub database_uri: String,
     pub backup_directory: String,
+    pub encryption_key_file: Option<String>,
 }
 
 fn get_env(key: &str) -> Result<String, String> {
@@ -17,9 +16,11 @@ impl Config {
     pub fn build() -> Result<Config, String> {
         let database_uri = get_env("database_uri")?;
         let backup_directory = get_env("backup_directory")?;
+        let encryption_key_file = dotenvy::var("encryption_key_file").ok();
         Ok(Config {
             database_uri,
-            backup_directory
+            backup_directory,
+            encryption_key_file
         })
     }
 
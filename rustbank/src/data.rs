@@ -77,17 +77,17 @@ impl Data for User {
     }
 }
 
-impl Into<mysql_async::Params> for User {
+impl From<User> for mysql_async::Params {
 
-    fn into(self) -> mysql_async::Params {
+    fn from(user: User) -> mysql_async::Params {
         mysql_async::Params::from((
-            self.first_name,
-            self.last_name,
-            self.email,
-            self.address,
-            self.phone_number,
-            self.date_of_birth,
-            self.created_at
+            user.first_name,
+            user.last_name,
+            user.email,
+            user.address,
+            user.phone_number,
+            user.date_of_birth,
+            user.created_at
         ))
     }
 }
@@ -151,15 +151,15 @@ impl Data for Account {
     }
 }
 
-impl Into<mysql_async::Params> for Account {
+impl From<Account> for mysql_async::Params {
 
-        fn into(self) -> mysql_async::Params {
+        fn from(account: Account) -> mysql_async::Params {
             mysql_async::Params::from((
-                self.user_id,
-                self.account_number,
-                self.currency_code,
-                self.balance,
-                self.created_at
+                account.user_id,
+                account.account_number,
+                account.currency_code,
+                account.balance,
+                account.created_at
             ))
         }
 }
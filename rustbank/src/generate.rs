@@ -1,13 +1,13 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use anyhow::Result;
 use serde::Serialize;
 use crate::data::{Account, Data, User};
 
-pub fn generate_set<T>(path: &PathBuf, name: String, rows: usize) -> Result<()> 
+pub fn generate_set<T>(path: &Path, name: String, rows: usize) -> Result<()> 
 where
 T: Data + Serialize
 {
-    let mut path = path.clone();
+    let mut path = path.to_path_buf();
     path.push(name);
     let mut writer = csv::Writer::from_path(path)?;
     for _ in 0..rows {
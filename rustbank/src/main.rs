@@ -1,6 +1,5 @@
 use std::{path::PathBuf, process};
 use anyhow::Result;
-use dotenvy;
 use clap::{Parser, Subcommand, Args};
 use rustbank::{backup, generate, upload, Config};
 
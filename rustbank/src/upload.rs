@@ -32,17 +32,15 @@ T: Data + Clone + Sync + Serialize + for<'de> Deserialize<'de> + Into<mysql_asyn
     let insert_statement = T::insert_query();
     let mut batch: Vec<T> = Vec::with_capacity(BATCH_SIZE);
 
-    let mut batches = 0;
     while let Some(record) = records.next().await {
         match record {
             Ok(record) => {
                 batch.push(record);
-                if batch.len() >= BATCH_SIZE {
-                    batches = batches + 1;
-                    if let Err(e) = upload_batch(&mut transaction, insert_statement.clone(), &mut batch).await {
-                        transaction.rollback().await?;
-                        bail!(e);
-                    }
+                if batch.len() >= BATCH_SIZE
+                    && let Err(e) = upload_batch(&mut transaction, insert_statement.clone(), &mut batch).await
+                {
+                    transaction.rollback().await?;
+                    bail!(e);
                 }
             },
             Err(e) => {
@@ -52,11 +50,11 @@ T: Data + Clone + Sync + Serialize + for<'de> Deserialize<'de> + Into<mysql_asyn
         }
     }
 
-    if batch.len() > 0 {
-                            if let Err(e) = upload_batch(&mut transaction, insert_statement.clone(), &mut batch).await {
-                                transaction.rollback().await?;
-                                bail!(e);
-                            }
+    if !batch.is_empty()
+        && let Err(e) = upload_batch(&mut transaction, insert_statement.clone(), &mut batch).await
+    {
+        transaction.rollback().await?;
+        bail!(e);
     }
     transaction.commit().await?;
     Ok(())
@@ -73,7 +71,7 @@ pub async fn upload_batch<T>(transaction: &mut Transaction<'_>, statement: Strin
 }
 
 pub async fn upload(config: &Config, path: PathBuf) -> Result<()> {
-    const FILES: [&'static str; 2] = ["users.csv", "accounts.csv"];
+    const FILES: [&str; 2] = ["users.csv", "accounts.csv"];
     let mut paths = Vec::new();
     for f in FILES {
         let mut path = path.clone();